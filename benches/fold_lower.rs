@@ -0,0 +1,59 @@
+//! Compares `FoldLowerFilter` against chaining `AsciiFoldingFilter` and `LowerCaser`.
+//!
+//! Run with `cargo bench --bench fold_lower`.
+
+use tantivy::tokenizer::{AsciiFoldingFilter, LowerCaser, TextAnalyzer, WhitespaceTokenizer};
+use tantivy_tokenizers::utils::measure_throughput;
+use tantivy_tokenizers::FoldLowerFilter;
+
+const ITERATIONS: usize = 2_000;
+
+const SAMPLES: [(&str, &str); 4] = [
+    (
+        "Latin",
+        "Café NAÏVE Crème Brûlée Ångström Straße Œuvre Łódź São Zürich Dvořák Ñandú \
+         façade The Quick Brown Fox jumps over the lazy dog ",
+    ),
+    (
+        "Greek/Cyrillic",
+        "Η Αθήνα είναι η ΠΡΩΤΕΥΟΥΣΑ της Ελλάδας Москва является СТОЛИЦЕЙ России ",
+    ),
+    (
+        "Vietnamese",
+        "Việt Nam có nhiều THÀNH PHỐ lớn như Hà Nội và Hồ Chí Minh ",
+    ),
+    (
+        "Decomposed",
+        "Cafe\u{301} NAI\u{308}VE Cre\u{300}me Vie\u{323}\u{302}t Zu\u{308}rich the lazy dog ",
+    ),
+];
+
+fn main() {
+    let mut fold_lower = TextAnalyzer::builder(WhitespaceTokenizer::default())
+        .filter(FoldLowerFilter)
+        .build();
+    let mut chained = TextAnalyzer::builder(WhitespaceTokenizer::default())
+        .filter(AsciiFoldingFilter)
+        .filter(LowerCaser)
+        .build();
+
+    for (name, sample) in SAMPLES {
+        let text = sample.repeat(50);
+        let single = measure_throughput(&mut fold_lower, &text, ITERATIONS);
+        let chain = measure_throughput(&mut chained, &text, ITERATIONS);
+
+        println!("{name}:");
+        println!(
+            "  FoldLowerFilter:                {:>8.1} MB/s",
+            single.bytes_per_sec / 1e6
+        );
+        println!(
+            "  AsciiFoldingFilter+LowerCaser:  {:>8.1} MB/s",
+            chain.bytes_per_sec / 1e6
+        );
+        println!(
+            "  speedup: {:.2}x",
+            single.bytes_per_sec / chain.bytes_per_sec
+        );
+    }
+}
@@ -1,7 +1,17 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
 use crate::filters::{
-    get_stopwords_filter_en, OuterPunctuationFilter, PossessiveContractionFilter,
+    get_stopwords_filter_en, get_stopwords_filter_multi, IdentifierSplitFilter,
+    OcrNormalizationFilter, OuterPunctuationFilter, PossessiveContractionFilter,
+    StopWordMarkFilter, TruncateTokenFilter,
+};
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, Language, LowerCaser, RegexTokenizer, RemoveLongFilter, SimpleTokenizer,
+    StopWordFilter, TextAnalyzer, TextAnalyzerBuilder, WhitespaceTokenizer,
 };
-use tantivy::tokenizer::{LowerCaser, StopWordFilter, TextAnalyzer, WhitespaceTokenizer};
 
 /// Creates the Kapiche tokenizer analyzer.
 /// Combines WhitespaceTokenizer with OuterPunctuationFilter and PossessiveContractionFilter.
@@ -55,6 +65,529 @@ pub fn kapiche_analyzer_lower_with_stopwords() -> TextAnalyzer {
         .build()
 }
 
+/// Creates a case-sensitive analyzer for searching source code.
+///
+/// This analyzer:
+/// - Tokenizes on identifiers: runs of letters, digits and underscores, so operators and
+///   punctuation ("user.getName()") separate tokens
+/// - Keeps each identifier and also emits its words, split on underscores and camelCase
+///   boundaries (e.g., "getUserID" -> "getUserID", "get", "User", "ID")
+/// - Preserves case and keeps stopwords, which are meaningful in code ("is", "if", "NOT")
+pub fn code_search_analyzer() -> TextAnalyzer {
+    let tokenizer = RegexTokenizer::new(r"[\p{L}\p{N}_]+").expect("identifier pattern is valid");
+    TextAnalyzer::builder(tokenizer)
+        .filter(IdentifierSplitFilter::new().keep_original(true))
+        .build()
+}
+
+/// Configuration for building a Kapiche analyzer with a custom set of filters.
+///
+/// The default configuration is equivalent to `kapiche_analyzer()`. Enabling
+/// `lowercase` and `stopwords` gives `kapiche_analyzer_lower_with_stopwords()`.
+///
+/// # Example
+/// ```
+/// use tantivy_tokenizers::analyzers::KapicheAnalyzerConfig;
+/// use tantivy_tokenizers::count_tokens;
+///
+/// let mut analyzer = KapicheAnalyzerConfig::new()
+///     .lowercase(true)
+///     .stopwords(true)
+///     .build();
+/// assert_eq!(count_tokens(&mut analyzer, "The quick brown fox"), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KapicheAnalyzerConfig {
+    pub(crate) lowercase: bool,
+    pub(crate) stopwords: bool,
+    pub(crate) mark_stopwords: bool,
+    pub(crate) leading_allow: Vec<char>,
+    pub(crate) expected_tokens_per_doc: usize,
+    pub(crate) assume_lowercase: bool,
+}
+
+impl Default for KapicheAnalyzerConfig {
+    fn default() -> Self {
+        KapicheAnalyzerConfig {
+            lowercase: false,
+            stopwords: false,
+            mark_stopwords: false,
+            leading_allow: vec!['#', '@'],
+            expected_tokens_per_doc: 0,
+            assume_lowercase: false,
+        }
+    }
+}
+
+impl KapicheAnalyzerConfig {
+    /// Creates a configuration equivalent to `kapiche_analyzer()`.
+    pub fn new() -> KapicheAnalyzerConfig {
+        KapicheAnalyzerConfig::default()
+    }
+
+    /// Converts tokens to lowercase before any other filtering.
+    pub fn lowercase(mut self, lowercase: bool) -> KapicheAnalyzerConfig {
+        self.lowercase = lowercase;
+        self
+    }
+
+    /// Removes Kapiche's custom English stopwords.
+    pub fn stopwords(mut self, stopwords: bool) -> KapicheAnalyzerConfig {
+        self.stopwords = stopwords;
+        self
+    }
+
+    /// Marks stopwords with `StopWordMarkFilter` instead of removing them.
+    ///
+    /// Only has an effect together with `stopwords(true)`. Stopwords are recognized at the
+    /// same point in the pipeline, but keep their text and get position `usize::MAX`, so
+    /// one pass gives both the full and the stopword-free output (see
+    /// `utils::dual_tokenize_with`).
+    pub fn mark_stopwords(mut self, mark_stopwords: bool) -> KapicheAnalyzerConfig {
+        self.mark_stopwords = mark_stopwords;
+        self
+    }
+
+    /// Sets the leading punctuation characters kept by `OuterPunctuationFilter`.
+    pub fn leading_allow(mut self, leading_allow: Vec<char>) -> KapicheAnalyzerConfig {
+        self.leading_allow = leading_allow;
+        self
+    }
+
+    /// Skips `LowerCaser` because the caller guarantees the input is already lowercase.
+    ///
+    /// Only has an effect together with `lowercase(true)`. Everything else, including
+    /// stopword matching, runs as usual, so lowercase input is analyzed exactly as by the
+    /// full lowercasing pipeline. Input that isn't lowercase keeps its case.
+    pub fn assume_lowercase(mut self, assume_lowercase: bool) -> KapicheAnalyzerConfig {
+        self.assume_lowercase = assume_lowercase;
+        self
+    }
+
+    /// Sets the expected number of tokens per document.
+    ///
+    /// This is only a capacity hint for the collecting utilities (see
+    /// `tokens_per_doc_hint`) and never changes the produced tokens.
+    pub fn expected_tokens_per_doc(mut self, tokens: usize) -> KapicheAnalyzerConfig {
+        self.expected_tokens_per_doc = tokens;
+        self
+    }
+
+    /// The expected number of tokens per document, 0 if no hint was given.
+    ///
+    /// Pass it to the `_with_capacity` variants of the collecting utilities in `utils`,
+    /// e.g. `tokens_with_positions_with_capacity`, to pre-size their output.
+    pub fn tokens_per_doc_hint(&self) -> usize {
+        self.expected_tokens_per_doc
+    }
+
+    /// Builds a `TextAnalyzer` from this configuration.
+    pub fn build(&self) -> TextAnalyzer {
+        let mut builder = TextAnalyzer::builder(WhitespaceTokenizer::default()).dynamic();
+        if self.lowercase && !self.assume_lowercase {
+            builder = builder.filter_dynamic(LowerCaser);
+        }
+        builder = builder.filter_dynamic(OuterPunctuationFilter::new(self.leading_allow.clone()));
+        if self.stopwords && self.mark_stopwords {
+            builder = builder.filter_dynamic(StopWordMarkFilter::new(get_stopwords_filter_en()));
+        } else if self.stopwords {
+            builder = builder.filter_dynamic(StopWordFilter::remove(get_stopwords_filter_en()));
+        }
+        builder.filter_dynamic(PossessiveContractionFilter).build()
+    }
+}
+
+static ANALYZER_CACHE: OnceLock<Mutex<HashMap<KapicheAnalyzerConfig, TextAnalyzer>>> =
+    OnceLock::new();
+static ANALYZER_CACHE_HITS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns an analyzer for `config`, built once per configuration and cached.
+///
+/// Later calls with an equal configuration return a clone of the cached analyzer,
+/// avoiding repeated stopword expansion. Cloning a `TextAnalyzer` clones its boxed
+/// filter chain, a few small allocations; the stopword set itself is shared behind an
+/// `Arc`, so a clone is much cheaper than building the analyzer again. The capacity hint
+/// set with `expected_tokens_per_doc` doesn't affect the analyzer and is ignored for caching.
+///
+/// # Example
+/// ```
+/// use tantivy_tokenizers::{analyzers, count_tokens, KapicheAnalyzerConfig};
+///
+/// let config = KapicheAnalyzerConfig::new().lowercase(true).stopwords(true);
+/// let mut analyzer = analyzers::cached(config.clone());
+/// let mut again = analyzers::cached(config);
+/// assert_eq!(count_tokens(&mut analyzer, "The quick brown fox"), 3);
+/// assert_eq!(count_tokens(&mut again, "The quick brown fox"), 3);
+/// ```
+pub fn cached(config: KapicheAnalyzerConfig) -> TextAnalyzer {
+    let key = config.expected_tokens_per_doc(0);
+    let mut cache = ANALYZER_CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(analyzer) = cache.get(&key) {
+        ANALYZER_CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+        return analyzer.clone();
+    }
+    let analyzer = key.build();
+    cache.insert(key, analyzer.clone());
+    analyzer
+}
+
+/// Number of `cached` calls that were served from the cache so far.
+pub fn cache_hits() -> usize {
+    ANALYZER_CACHE_HITS.load(Ordering::Relaxed)
+}
+
+// Probe strings for `behavior_fingerprint`, covering case, punctuation, hashtags and
+// mentions, possessives and contractions, stopwords, accents, numbers and emoji.
+const FINGERPRINT_PROBES: [&str; 8] = [
+    "The quick brown fox jumps over the lazy dog.",
+    "#HashTag @Mention John's don\u{2019}t y'all'd've",
+    "\"Quoted\" (bracketed) ...ellipsis... --dashes-- ¿Qué?",
+    "Café NAÏVE Straße Ångström e\u{301}",
+    "U.S.A. example.com node.js v1.2.3 12.5% 2020\u{2013}2024",
+    "\u{1F333} tree\u{1F333} \u{1F44D}\u{1F3FD} \u{1F1E6}\u{1F1FA}",
+    "I'm sure it's the best, isn't it? They'd've known.",
+    "  multiple   spaces\tand\nnewlines\r\n",
+];
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// 64-bit FNV-1a, used instead of `DefaultHasher` because its output is stable across
+// Rust versions and platforms.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Computes a deterministic fingerprint of an analyzer's behavior.
+///
+/// A fixed set of probe strings is run through the analyzer and the text and position of
+/// every token (stopped tokens included) are hashed with FNV-1a. Analyzers with the same
+/// fingerprint tokenize the probes identically, so the fingerprint can be stored with an
+/// index or cache and compared to detect tokenization changes. It is stable across runs,
+/// platforms and Rust versions, but changes whenever the probe set changes.
+///
+/// # Example
+/// ```
+/// use tantivy_tokenizers::analyzers::{behavior_fingerprint, kapiche_analyzer_lower};
+///
+/// let fingerprint = behavior_fingerprint(&mut kapiche_analyzer_lower());
+/// assert_eq!(fingerprint, behavior_fingerprint(&mut kapiche_analyzer_lower()));
+/// ```
+pub fn behavior_fingerprint(analyzer: &mut TextAnalyzer) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for probe in FINGERPRINT_PROBES {
+        let mut token_stream = analyzer.token_stream(probe);
+        while token_stream.advance() {
+            let token = token_stream.token();
+            hash = fnv1a(hash, token.text.as_bytes());
+            // 0xFF never occurs in UTF-8, so it separates tokens unambiguously
+            hash = fnv1a(hash, &[0xFF]);
+            hash = fnv1a(hash, &(token.position as u64).to_le_bytes());
+        }
+        hash = fnv1a(hash, &[0xFE]);
+    }
+    hash
+}
+
+/// Error returned by `from_spec` when an analyzer spec cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpecError {
+    /// The spec did not contain a tokenizer.
+    Empty,
+    /// The first element of the spec is not a known tokenizer.
+    UnknownTokenizer(String),
+    /// A filter in the spec is not known.
+    UnknownFilter(String),
+    /// A tokenizer or filter was given invalid or badly formatted arguments.
+    MalformedArgs { name: String, reason: String },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecError::Empty => write!(f, "analyzer spec is empty"),
+            SpecError::UnknownTokenizer(name) => write!(f, "unknown tokenizer '{}'", name),
+            SpecError::UnknownFilter(name) => write!(f, "unknown filter '{}'", name),
+            SpecError::MalformedArgs { name, reason } => {
+                write!(f, "malformed arguments for '{}': {}", name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+// Splits a spec element like "punct(#,@)" into its name and arguments.
+fn parse_spec_element(element: &str) -> Result<(&str, Vec<&str>), SpecError> {
+    let element = element.trim();
+    let Some(open) = element.find('(') else {
+        if element.contains(')') {
+            return Err(SpecError::MalformedArgs {
+                name: element.to_string(),
+                reason: "unexpected ')'".to_string(),
+            });
+        }
+        return Ok((element, Vec::new()));
+    };
+
+    let name = element[..open].trim();
+    let Some(args) = element[open + 1..].strip_suffix(')') else {
+        return Err(SpecError::MalformedArgs {
+            name: name.to_string(),
+            reason: "missing closing ')'".to_string(),
+        });
+    };
+    if args.contains(['(', ')']) {
+        return Err(SpecError::MalformedArgs {
+            name: name.to_string(),
+            reason: "nested parentheses".to_string(),
+        });
+    }
+    let args = if args.trim().is_empty() {
+        Vec::new()
+    } else {
+        args.split(',').map(str::trim).collect()
+    };
+    Ok((name, args))
+}
+
+fn malformed(name: &str, reason: &str) -> SpecError {
+    SpecError::MalformedArgs {
+        name: name.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn expect_no_args(name: &str, args: &[&str]) -> Result<(), SpecError> {
+    if args.is_empty() {
+        Ok(())
+    } else {
+        Err(malformed(name, "takes no arguments"))
+    }
+}
+
+fn expect_usize_arg(name: &str, args: &[&str]) -> Result<usize, SpecError> {
+    match args {
+        [arg] => arg
+            .parse()
+            .map_err(|_| malformed(name, &format!("'{}' is not a number", arg))),
+        _ => Err(malformed(name, "expects exactly one numeric argument")),
+    }
+}
+
+fn char_args(name: &str, args: &[&str]) -> Result<Vec<char>, SpecError> {
+    args.iter()
+        .map(|arg| {
+            let mut chars = arg.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(malformed(
+                    name,
+                    &format!("'{}' is not a single character", arg),
+                )),
+            }
+        })
+        .collect()
+}
+
+fn stopword_languages(name: &str, args: &[&str]) -> Result<Vec<Language>, SpecError> {
+    if args.is_empty() {
+        return Ok(vec![Language::English]);
+    }
+    args.iter()
+        .map(|arg| match *arg {
+            "en" => Ok(Language::English),
+            "es" => Ok(Language::Spanish),
+            _ => Err(malformed(
+                name,
+                &format!("unsupported stopword language '{}'", arg),
+            )),
+        })
+        .collect()
+}
+
+/// Constructor for a spec filter: adds the filter to the builder given the element's arguments.
+pub type SpecFilterConstructor = Box<
+    dyn Fn(TextAnalyzerBuilder, &[&str]) -> Result<TextAnalyzerBuilder, SpecError> + Send + Sync,
+>;
+
+/// The filters `from_spec` can reference by name.
+///
+/// `SpecRegistry::new()` starts with the built-in filters listed on `from_spec`; custom filters
+/// are added with `register`. Registering an existing name replaces that filter.
+///
+/// # Example
+/// ```
+/// use tantivy::tokenizer::LowerCaser;
+/// use tantivy_tokenizers::analyzers::SpecRegistry;
+/// use tantivy_tokenizers::count_tokens;
+///
+/// let registry = SpecRegistry::new().register("my_lower", |builder, _args| {
+///     Ok(builder.filter_dynamic(LowerCaser))
+/// });
+/// let mut analyzer = registry.from_spec("whitespace|my_lower|stop").unwrap();
+/// assert_eq!(count_tokens(&mut analyzer, "The best"), 1);
+/// ```
+pub struct SpecRegistry {
+    filters: HashMap<String, SpecFilterConstructor>,
+}
+
+impl Default for SpecRegistry {
+    fn default() -> Self {
+        SpecRegistry::new()
+    }
+}
+
+impl SpecRegistry {
+    /// Creates a registry with the built-in filters.
+    pub fn new() -> SpecRegistry {
+        let registry = SpecRegistry {
+            filters: HashMap::new(),
+        };
+        registry
+            .register("lower", |builder, args| {
+                expect_no_args("lower", args)?;
+                Ok(builder.filter_dynamic(LowerCaser))
+            })
+            .register("ascii_folding", |builder, args| {
+                expect_no_args("ascii_folding", args)?;
+                Ok(builder.filter_dynamic(AsciiFoldingFilter))
+            })
+            .register("punct", |builder, args| {
+                Ok(builder.filter_dynamic(OuterPunctuationFilter::new(char_args("punct", args)?)))
+            })
+            .register("stop", |builder, args| {
+                Ok(
+                    builder.filter_dynamic(StopWordFilter::remove(get_stopwords_filter_multi(
+                        &stopword_languages("stop", args)?,
+                    ))),
+                )
+            })
+            .register("possessive", |builder, args| {
+                expect_no_args("possessive", args)?;
+                Ok(builder.filter_dynamic(PossessiveContractionFilter))
+            })
+            .register("ocr", |builder, args| {
+                expect_no_args("ocr", args)?;
+                Ok(builder.filter_dynamic(OcrNormalizationFilter::new()))
+            })
+            .register("truncate", |builder, args| {
+                Ok(
+                    builder.filter_dynamic(TruncateTokenFilter::new(expect_usize_arg(
+                        "truncate", args,
+                    )?)),
+                )
+            })
+            .register("remove_long", |builder, args| {
+                Ok(
+                    builder.filter_dynamic(RemoveLongFilter::limit(expect_usize_arg(
+                        "remove_long",
+                        args,
+                    )?)),
+                )
+            })
+    }
+
+    /// Registers a filter constructor under `name`, replacing any filter with that name.
+    /// The constructor receives the arguments given in parentheses in the spec, and should
+    /// return `SpecError::MalformedArgs` if they are invalid.
+    pub fn register<F>(mut self, name: &str, constructor: F) -> SpecRegistry
+    where
+        F: Fn(TextAnalyzerBuilder, &[&str]) -> Result<TextAnalyzerBuilder, SpecError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.filters.insert(name.to_string(), Box::new(constructor));
+        self
+    }
+
+    /// Checks if a filter is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.filters.contains_key(name)
+    }
+
+    fn add_filter(
+        &self,
+        builder: TextAnalyzerBuilder,
+        name: &str,
+        args: &[&str],
+    ) -> Result<TextAnalyzerBuilder, SpecError> {
+        match self.filters.get(name) {
+            Some(constructor) => constructor(builder, args),
+            None => Err(SpecError::UnknownFilter(name.to_string())),
+        }
+    }
+
+    /// Builds an analyzer from a spec string, as `from_spec` does, with the filters in this
+    /// registry.
+    pub fn from_spec(&self, spec: &str) -> Result<TextAnalyzer, SpecError> {
+        let mut elements = spec.split('|').filter(|element| !element.trim().is_empty());
+        let (tokenizer, args) = parse_spec_element(elements.next().ok_or(SpecError::Empty)?)?;
+
+        let mut builder = match tokenizer {
+            "whitespace" => {
+                expect_no_args(tokenizer, &args)?;
+                TextAnalyzer::builder(WhitespaceTokenizer::default()).dynamic()
+            }
+            "simple" => {
+                expect_no_args(tokenizer, &args)?;
+                TextAnalyzer::builder(SimpleTokenizer::default()).dynamic()
+            }
+            _ => return Err(SpecError::UnknownTokenizer(tokenizer.to_string())),
+        };
+
+        for element in elements {
+            let (name, args) = parse_spec_element(element)?;
+            builder = self.add_filter(builder, name, &args)?;
+        }
+
+        Ok(builder.build())
+    }
+}
+
+static DEFAULT_SPEC_REGISTRY: OnceLock<SpecRegistry> = OnceLock::new();
+
+/// Builds an analyzer from a compact, pipe-delimited spec string.
+///
+/// The first element names the tokenizer and each following element adds a filter,
+/// in order. Arguments are given in parentheses and separated by commas.
+///
+/// Tokenizers:
+/// - `whitespace` - `WhitespaceTokenizer`
+/// - `simple` - `SimpleTokenizer`
+///
+/// Filters:
+/// - `lower` - `LowerCaser`
+/// - `ascii_folding` - `AsciiFoldingFilter`
+/// - `punct(chars...)` - `OuterPunctuationFilter` keeping the given leading characters
+/// - `stop` / `stop(langs...)` - Stopword removal, Kapiche English by default (`en`, `es`)
+/// - `possessive` - `PossessiveContractionFilter`
+/// - `ocr` - `OcrNormalizationFilter` with the default rules
+/// - `truncate(n)` - `TruncateTokenFilter` keeping `n` grapheme clusters
+/// - `remove_long(n)` - `RemoveLongFilter` dropping tokens of `n` bytes or more
+///
+/// Use a `SpecRegistry` to reference custom filters from a spec.
+///
+/// # Example
+/// ```
+/// use tantivy_tokenizers::analyzers::from_spec;
+/// use tantivy_tokenizers::count_tokens;
+///
+/// let mut analyzer = from_spec("whitespace|lower|punct(#,@)|stop|possessive").unwrap();
+/// assert_eq!(count_tokens(&mut analyzer, "The John's best!"), 2);
+/// ```
+pub fn from_spec(spec: &str) -> Result<TextAnalyzer, SpecError> {
+    DEFAULT_SPEC_REGISTRY
+        .get_or_init(SpecRegistry::new)
+        .from_spec(spec)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +633,36 @@ mod tests {
         assert_eq!(tokens, vec!["quick", "brown", "fox"]);
     }
 
+    #[test]
+    fn test_code_search_analyzer() {
+        let mut analyzer = code_search_analyzer();
+        let mut token_stream = analyzer.token_stream("getUserID user_id if (The.isOK)");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.text.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        // Case is preserved, identifiers are kept whole and split, stopwords stay
+        assert_eq!(
+            tokens,
+            vec![
+                "getUserID",
+                "get",
+                "User",
+                "ID",
+                "user_id",
+                "user",
+                "id",
+                "if",
+                "The",
+                "isOK",
+                "is",
+                "OK"
+            ]
+        );
+    }
+
     #[test]
     fn test_stopwords_removed_after_lowercasing() {
         let mut analyzer = kapiche_analyzer_lower_with_stopwords();
@@ -129,4 +692,212 @@ mod tests {
         // "best!" -> "best" (punctuation removed)
         assert_eq!(tokens, vec!["john", "best"]);
     }
+
+    #[test]
+    fn test_kapiche_analyzer_config_matches_prebuilt_analyzers() {
+        let text = "#HashTag The John's best! @Mention";
+        let collect = |analyzer: &mut TextAnalyzer| {
+            let mut token_stream = analyzer.token_stream(text);
+            let mut tokens = vec![];
+            let mut add_token = |token: &Token| {
+                tokens.push(token.text.clone());
+            };
+            token_stream.process(&mut add_token);
+            tokens
+        };
+
+        assert_eq!(
+            collect(&mut KapicheAnalyzerConfig::new().build()),
+            collect(&mut kapiche_analyzer())
+        );
+        assert_eq!(
+            collect(&mut KapicheAnalyzerConfig::new().lowercase(true).build()),
+            collect(&mut kapiche_analyzer_lower())
+        );
+        assert_eq!(
+            collect(
+                &mut KapicheAnalyzerConfig::new()
+                    .lowercase(true)
+                    .stopwords(true)
+                    .build()
+            ),
+            collect(&mut kapiche_analyzer_lower_with_stopwords())
+        );
+    }
+
+    #[test]
+    fn test_assume_lowercase_matches_lower_pipeline() {
+        let text = "#hashtag the john's best! @mention don't stop";
+        let collect = |analyzer: &mut TextAnalyzer| {
+            let mut token_stream = analyzer.token_stream(text);
+            let mut tokens = vec![];
+            let mut add_token = |token: &Token| {
+                tokens.push(token.clone());
+            };
+            token_stream.process(&mut add_token);
+            tokens
+        };
+        let config = KapicheAnalyzerConfig::new()
+            .lowercase(true)
+            .stopwords(true)
+            .assume_lowercase(true);
+
+        assert_eq!(
+            collect(&mut config.build()),
+            collect(&mut kapiche_analyzer_lower_with_stopwords())
+        );
+        assert_eq!(
+            collect(&mut config.stopwords(false).build()),
+            collect(&mut kapiche_analyzer_lower())
+        );
+    }
+
+    #[test]
+    fn test_cached_analyzer() {
+        let text = "The John's best! @Mention";
+        let collect = |analyzer: &mut TextAnalyzer| {
+            let mut token_stream = analyzer.token_stream(text);
+            let mut tokens = vec![];
+            let mut add_token = |token: &Token| {
+                tokens.push(token.text.clone());
+            };
+            token_stream.process(&mut add_token);
+            tokens
+        };
+        // A configuration no other test uses, so the first call is a miss
+        let config = KapicheAnalyzerConfig::new()
+            .lowercase(true)
+            .stopwords(true)
+            .leading_allow(vec!['@', '$', '~']);
+
+        let mut first = cached(config.clone());
+        let hits = cache_hits();
+        let mut second = cached(config.clone().expected_tokens_per_doc(64));
+        assert!(cache_hits() > hits);
+
+        assert_eq!(collect(&mut first), collect(&mut second));
+        assert_eq!(collect(&mut second), collect(&mut config.build()));
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        // Reference values for 64-bit FNV-1a
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_behavior_fingerprint() {
+        let fingerprint = behavior_fingerprint(&mut kapiche_analyzer_lower());
+        // Pinned: a change to the probes, the hashing or the pipeline must update this value
+        assert_eq!(fingerprint, 0xce26188035a21af2);
+        // The config builder produces the same pipeline
+        assert_eq!(
+            fingerprint,
+            behavior_fingerprint(&mut KapicheAnalyzerConfig::new().lowercase(true).build())
+        );
+        assert_ne!(fingerprint, behavior_fingerprint(&mut kapiche_analyzer()));
+        assert_ne!(
+            fingerprint,
+            behavior_fingerprint(&mut kapiche_analyzer_lower_with_stopwords())
+        );
+    }
+
+    #[test]
+    fn test_from_spec_valid() {
+        let mut analyzer = from_spec("whitespace|lower|punct(#,@)|stop|possessive").unwrap();
+        let mut token_stream = analyzer.token_stream("#HashTag The John's best!");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.text.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        assert_eq!(tokens, vec!["#hashtag", "john", "best"]);
+    }
+
+    #[test]
+    fn test_from_spec_with_numeric_args() {
+        let mut analyzer = from_spec(" simple | lower | truncate(4) ").unwrap();
+        let mut token_stream = analyzer.token_stream("Internationalization");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.text.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        assert_eq!(tokens, vec!["inte"]);
+    }
+
+    #[test]
+    fn test_from_spec_unknown_names() {
+        assert_eq!(
+            from_spec("whitespace|lower|stem").err(),
+            Some(SpecError::UnknownFilter("stem".to_string()))
+        );
+        assert_eq!(
+            from_spec("ngram|lower").err(),
+            Some(SpecError::UnknownTokenizer("ngram".to_string()))
+        );
+        assert_eq!(from_spec("").err(), Some(SpecError::Empty));
+    }
+
+    #[test]
+    fn test_from_spec_malformed_args() {
+        let cases = [
+            "whitespace|punct(#,@",
+            "whitespace|punct(##)",
+            "whitespace|truncate(ten)",
+            "whitespace|truncate",
+            "whitespace|lower(1)",
+            "whitespace|stop(fr)",
+            "whitespace|lower)",
+        ];
+        for spec in cases {
+            let err = from_spec(spec).err();
+            assert!(
+                matches!(err, Some(SpecError::MalformedArgs { .. })),
+                "expected malformed args for {} but {:?}",
+                spec,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_spec_registry_custom_filter() {
+        // Keeps tokens of at most n bytes
+        let registry = SpecRegistry::new().register("max_len", |builder, args| {
+            let n = match args {
+                [arg] => arg.parse::<usize>().map_err(|_| SpecError::MalformedArgs {
+                    name: "max_len".to_string(),
+                    reason: format!("'{}' is not a number", arg),
+                })?,
+                _ => 3,
+            };
+            Ok(builder.filter_dynamic(RemoveLongFilter::limit(n + 1)))
+        });
+        assert!(registry.contains("max_len"));
+        assert!(registry.contains("lower"));
+
+        let mut analyzer = registry.from_spec("whitespace|lower|max_len(4)").unwrap();
+        let mut token_stream = analyzer.token_stream("A tiny example");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.text.clone());
+        };
+        token_stream.process(&mut add_token);
+        assert_eq!(tokens, vec!["a", "tiny"]);
+
+        assert!(matches!(
+            registry.from_spec("whitespace|max_len(x)").err(),
+            Some(SpecError::MalformedArgs { .. })
+        ));
+        // Custom filters are not visible to the default registry
+        assert_eq!(
+            from_spec("whitespace|max_len").err(),
+            Some(SpecError::UnknownFilter("max_len".to_string()))
+        );
+    }
 }
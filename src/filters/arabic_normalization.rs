@@ -0,0 +1,145 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'ArabicNormalizationFilter' folds common Arabic and Persian letter variants to a
+// canonical form, like Elasticsearch's arabic_normalization and persian_normalization.
+// Hamza and madda forms of alef ("أ", "إ", "آ", "ٱ") become a bare alef "ا", alef maksura
+// "ى" and Persian yeh "ی" become Arabic yeh "ي", teh marbuta "ة" and the Persian heh forms
+// become heh "ه", and Persian keheh "ک" becomes Arabic kaf "ك".
+// Tatweel (the elongation character) and harakat (short vowel marks) are removed.
+// Tokens without Arabic-script characters are passed through unchanged.
+
+#[derive(Clone)]
+pub struct ArabicNormalizationFilter;
+
+impl TokenFilter for ArabicNormalizationFilter {
+    type Tokenizer<T: Tokenizer> = ArabicNormalizationFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> ArabicNormalizationFilterWrapper<T> {
+        ArabicNormalizationFilterWrapper { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct ArabicNormalizationFilterWrapper<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for ArabicNormalizationFilterWrapper<T> {
+    type TokenStream<'a> = ArabicNormalizationFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        ArabicNormalizationFilterTokenStream {
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct ArabicNormalizationFilterTokenStream<T> {
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+// Returns the canonical form of `c`, or None if it should be removed.
+fn normalize_char(c: char) -> Option<char> {
+    match c {
+        // Alef with madda, hamza above, hamza below and wasla -> alef
+        '\u{0622}' | '\u{0623}' | '\u{0625}' | '\u{0671}' => Some('\u{0627}'),
+        // Alef maksura and Persian yeh -> yeh
+        '\u{0649}' | '\u{06CC}' => Some('\u{064A}'),
+        // Teh marbuta, heh with yeh above and heh goal -> heh
+        '\u{0629}' | '\u{06C0}' | '\u{06C1}' => Some('\u{0647}'),
+        // Keheh -> kaf
+        '\u{06A9}' => Some('\u{0643}'),
+        // Tatweel, harakat (fathatan to sukun) and superscript alef
+        '\u{0640}' | '\u{064B}'..='\u{0652}' | '\u{0670}' => None,
+        _ => Some(c),
+    }
+}
+
+impl<T: TokenStream> TokenStream for ArabicNormalizationFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = &self.tail.token().text;
+        // Fast path: no Arabic-script characters
+        if !text.chars().any(|c| ('\u{0600}'..='\u{06FF}').contains(&c)) {
+            return true;
+        }
+        self.buffer.clear();
+        self.buffer.extend(text.chars().filter_map(normalize_char));
+        mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::ArabicNormalizationFilter;
+
+    #[test]
+    fn test_arabic_normalization_filter_alef_variants() {
+        // "أحمد", "إحمد", "آحمد" and "احمد" all normalize to the bare alef form
+        let tokens = token_stream_helper(
+            "\u{0623}\u{062D}\u{0645}\u{062F} \u{0625}\u{062D}\u{0645}\u{062F} \
+             \u{0622}\u{062D}\u{0645}\u{062F} \u{0627}\u{062D}\u{0645}\u{062F}",
+        );
+        assert_eq!(tokens.len(), 4);
+        for token in &tokens {
+            assert_eq!(token.text, "\u{0627}\u{062D}\u{0645}\u{062F}");
+        }
+        assert_token(&tokens[1], 1, "\u{0627}\u{062D}\u{0645}\u{062F}", 9, 17);
+    }
+
+    #[test]
+    fn test_arabic_normalization_filter_persian_variants() {
+        // Persian "کی" and Arabic "كي" normalize to the same token
+        let tokens = token_stream_helper("\u{06A9}\u{06CC} \u{0643}\u{064A}");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "\u{0643}\u{064A}", 0, 4);
+        assert_token(&tokens[1], 1, "\u{0643}\u{064A}", 5, 9);
+    }
+
+    #[test]
+    fn test_arabic_normalization_filter_marks_and_other_scripts() {
+        // Teh marbuta, tatweel and a fatha are normalized; Latin text is left alone
+        let tokens = token_stream_helper(
+            "\u{0645}\u{064E}\u{062F}\u{0631}\u{0633}\u{0640}\u{0629} Caf\u{e9}",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["\u{0645}\u{062F}\u{0631}\u{0633}\u{0647}", "Caf\u{e9}"]
+        );
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(ArabicNormalizationFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,175 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
+
+// 'BidiControlFilter' guards against malicious or malformed text using bidirectional
+// controls such as RIGHT-TO-LEFT OVERRIDE (U+202E), which make text render differently from
+// its logical order. Tokens made only of invisible format characters (general category Cf,
+// which covers every bidi control) carry no content and are dropped.
+// With `strip_embedded(true)` the bidi controls are also removed from inside otherwise
+// normal tokens, so "pay\u{202E}fdp.exe" is indexed as "payfdp.exe". Only the bidi controls
+// are stripped: other format characters such as the zero-width joiner in emoji sequences
+// are kept.
+
+const BIDI_CONTROLS: [char; 12] = [
+    '\u{061C}', // Arabic letter mark
+    '\u{200E}', // Left-to-right mark
+    '\u{200F}', // Right-to-left mark
+    '\u{202A}', // Left-to-right embedding
+    '\u{202B}', // Right-to-left embedding
+    '\u{202C}', // Pop directional formatting
+    '\u{202D}', // Left-to-right override
+    '\u{202E}', // Right-to-left override
+    '\u{2066}', // Left-to-right isolate
+    '\u{2067}', // Right-to-left isolate
+    '\u{2068}', // First strong isolate
+    '\u{2069}', // Pop directional isolate
+];
+
+#[derive(Clone, Default)]
+pub struct BidiControlFilter {
+    strip_embedded: bool,
+}
+
+impl BidiControlFilter {
+    /// Creates a `BidiControlFilter` that drops tokens made only of format characters.
+    pub fn new() -> BidiControlFilter {
+        BidiControlFilter::default()
+    }
+
+    /// Whether bidi controls are stripped from inside other tokens. Defaults to false.
+    pub fn strip_embedded(mut self, strip: bool) -> BidiControlFilter {
+        self.strip_embedded = strip;
+        self
+    }
+}
+
+impl TokenFilter for BidiControlFilter {
+    type Tokenizer<T: Tokenizer> = BidiControlFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> BidiControlFilterWrapper<T> {
+        BidiControlFilterWrapper {
+            strip_embedded: self.strip_embedded,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BidiControlFilterWrapper<T> {
+    strip_embedded: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for BidiControlFilterWrapper<T> {
+    type TokenStream<'a> = BidiControlFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        BidiControlFilterTokenStream {
+            strip_embedded: self.strip_embedded,
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct BidiControlFilterTokenStream<T> {
+    strip_embedded: bool,
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+// Checks if text is non-empty and made only of format characters.
+fn is_format_only(text: &str) -> bool {
+    !text.is_ascii()
+        && text
+            .chars()
+            .all(|c| c.general_category() == GeneralCategory::Format)
+}
+
+impl<T: TokenStream> BidiControlFilterTokenStream<T> {
+    // Removes bidi controls from the current tail token.
+    fn strip(&mut self) {
+        let text = &self.tail.token().text;
+        // Fast path: nothing to strip
+        if text.is_ascii() || !text.contains(BIDI_CONTROLS) {
+            return;
+        }
+        self.buffer.clear();
+        self.buffer
+            .extend(text.chars().filter(|c| !BIDI_CONTROLS.contains(c)));
+        mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+    }
+}
+
+impl<T: TokenStream> TokenStream for BidiControlFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if is_format_only(&self.tail.token().text) {
+                continue;
+            }
+            if self.strip_embedded {
+                self.strip();
+            }
+            return true;
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::BidiControlFilter;
+
+    #[test]
+    fn test_bidi_control_filter() {
+        let tokens = token_stream_helper(
+            BidiControlFilter::new(),
+            "open \u{202E}\u{202C} invoice \u{2066}\u{200F}\u{2069} pay\u{202E}fdp.exe",
+        );
+        // Tokens made only of controls are dropped; embedded controls are kept by default
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "open", 0, 4);
+        assert_token(&tokens[1], 2, "invoice", 12, 19);
+        assert_token(&tokens[2], 4, "pay\u{202E}fdp.exe", 30, 43);
+    }
+
+    #[test]
+    fn test_bidi_control_filter_strip_embedded() {
+        let tokens = token_stream_helper(
+            BidiControlFilter::new().strip_embedded(true),
+            "pay\u{202E}fdp.exe \u{200E} \u{1F469}\u{200D}\u{1F4BB}",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // The zero-width joiner is a format character but not a bidi control
+        assert_eq!(texts, vec!["payfdp.exe", "\u{1F469}\u{200D}\u{1F4BB}"]);
+        assert_token(&tokens[0], 0, "payfdp.exe", 0, 13);
+    }
+
+    fn token_stream_helper(filter: BidiControlFilter, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,154 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'CharNgramFilter' replaces each token with its overlapping character n-grams for
+// typo-tolerant matching, e.g. "hello" with n = 3 -> "hel", "ell", "llo".
+// The n-grams share the position and offsets of the original token. Tokens shorter than
+// n characters are emitted whole, so short words are never lost, and stopped tokens
+// (position usize::MAX) are passed through unchanged. An n of 0 is treated as 1.
+
+#[derive(Clone)]
+pub struct CharNgramFilter {
+    n: usize,
+}
+
+impl CharNgramFilter {
+    /// Creates a `CharNgramFilter` emitting character n-grams of length `n`.
+    pub fn new(n: usize) -> CharNgramFilter {
+        CharNgramFilter { n: n.max(1) }
+    }
+}
+
+impl TokenFilter for CharNgramFilter {
+    type Tokenizer<T: Tokenizer> = CharNgramFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> CharNgramFilterWrapper<T> {
+        CharNgramFilterWrapper {
+            n: self.n,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CharNgramFilterWrapper<T> {
+    n: usize,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for CharNgramFilterWrapper<T> {
+    type TokenStream<'a> = CharNgramFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        CharNgramFilterTokenStream {
+            n: self.n,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct CharNgramFilterTokenStream<T> {
+    n: usize,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+impl<T: TokenStream> CharNgramFilterTokenStream<T> {
+    fn split(&mut self) {
+        let token = self.tail.token();
+        if token.position == usize::MAX {
+            return;
+        }
+        // Byte offsets of each character, plus the end of the text
+        let bounds: Vec<usize> = token
+            .text
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain(Some(token.text.len()))
+            .collect();
+        if bounds.len() <= self.n + 1 {
+            return;
+        }
+        for window in bounds.windows(self.n + 1).rev() {
+            self.parts.push(Token {
+                text: token.text[window[0]..window[self.n]].to_string(),
+                ..token.clone()
+            });
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for CharNgramFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.split();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::CharNgramFilter;
+
+    #[test]
+    fn test_char_ngram_filter() {
+        let tokens = token_stream_helper("hello", 3);
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "hel", 0, 5);
+        assert_token(&tokens[1], 0, "ell", 0, 5);
+        assert_token(&tokens[2], 0, "llo", 0, 5);
+    }
+
+    #[test]
+    fn test_char_ngram_filter_short_tokens() {
+        let tokens = token_stream_helper("hi hey", 3);
+        assert_eq!(tokens.len(), 2);
+        // Shorter than n, so emitted whole
+        assert_token(&tokens[0], 0, "hi", 0, 2);
+        assert_token(&tokens[1], 1, "hey", 3, 6);
+    }
+
+    #[test]
+    fn test_char_ngram_filter_multibyte() {
+        let tokens = token_stream_helper("café", 2);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["ca", "af", "fé"]);
+    }
+
+    fn token_stream_helper(text: &str, n: usize) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(CharNgramFilter::new(n))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,166 @@
+use crate::filters::APOSTROPHES;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'CliticSplitFilter' separates English clitics from their base word for grammar features,
+// e.g. "we'll" -> "we", "'ll" and "can't" -> "ca", "n't".
+// The recognized clitics are 'll, 're, 've, 'd, 'm and n't, written with any apostrophe
+// variant. Clitics are emitted as marked tokens starting with an ASCII apostrophe ("'ll",
+// "n't") so they never collide with ordinary words, and they share the position of the
+// original token. Chains are fully split ("shouldn't've" -> "should", "n't", "'ve").
+// Like the Penn Treebank, "n't" is split off as-is, so "can't" and "won't" leave the bases
+// "ca" and "wo". The possessive 's is not a clitic here; see `PossessiveContractionFilter`.
+
+const CLITICS: [&str; 5] = ["ll", "re", "ve", "d", "m"];
+
+#[derive(Clone)]
+pub struct CliticSplitFilter;
+
+impl TokenFilter for CliticSplitFilter {
+    type Tokenizer<T: Tokenizer> = CliticSplitFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> CliticSplitFilterWrapper<T> {
+        CliticSplitFilterWrapper { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct CliticSplitFilterWrapper<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for CliticSplitFilterWrapper<T> {
+    type TokenStream<'a> = CliticSplitFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        CliticSplitFilterTokenStream {
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct CliticSplitFilterTokenStream<T> {
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+// Splits trailing clitics off `text`, returning the base and the marked clitics in reverse
+// order, or None if the text doesn't end in a clitic.
+fn split_clitics(text: &str) -> Option<(&str, Vec<String>)> {
+    let mut base = text;
+    let mut clitics = Vec::new();
+    while let Some((idx, apos)) = base
+        .char_indices()
+        .rev()
+        .find(|(_, c)| APOSTROPHES.contains(c))
+    {
+        let (head, suffix) = (&base[..idx], &base[idx + apos.len_utf8()..]);
+        if !head.is_empty()
+            && CLITICS
+                .iter()
+                .any(|clitic| clitic.eq_ignore_ascii_case(suffix))
+        {
+            clitics.push(format!("'{}", suffix));
+            base = head;
+        } else if suffix.eq_ignore_ascii_case("t") && head.len() > 1 && head.ends_with(['n', 'N']) {
+            let n = &head[head.len() - 1..];
+            clitics.push(format!("{}'{}", n, suffix));
+            base = &head[..head.len() - 1];
+        } else {
+            break;
+        }
+    }
+    (!clitics.is_empty()).then_some((base, clitics))
+}
+
+impl<T: TokenStream> CliticSplitFilterTokenStream<T> {
+    fn split(&mut self) {
+        let token = self.tail.token();
+        let Some((base, clitics)) = split_clitics(&token.text) else {
+            return;
+        };
+        let base = base.to_string();
+        for text in clitics.into_iter().chain(Some(base)) {
+            self.parts.push(Token {
+                text,
+                ..token.clone()
+            });
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for CliticSplitFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.split();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::CliticSplitFilter;
+
+    #[test]
+    fn test_clitic_split_filter() {
+        let tokens = token_stream_helper("we'll they're can't");
+        assert_eq!(tokens.len(), 6);
+        assert_token(&tokens[0], 0, "we", 0, 5);
+        assert_token(&tokens[1], 0, "'ll", 0, 5);
+        assert_token(&tokens[2], 1, "they", 6, 13);
+        assert_token(&tokens[3], 1, "'re", 6, 13);
+        assert_token(&tokens[4], 2, "ca", 14, 19);
+        assert_token(&tokens[5], 2, "n't", 14, 19);
+    }
+
+    #[test]
+    fn test_clitic_split_filter_apostrophe_variants_and_chains() {
+        let tokens = token_stream_helper("We\u{2019}VE I\u{02BC}m shouldn't've");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["We", "'VE", "I", "'m", "should", "n't", "'ve"]);
+    }
+
+    #[test]
+    fn test_clitic_split_filter_non_clitics() {
+        let tokens = token_stream_helper("John's rock'n'roll 'll n't don");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["John's", "rock'n'roll", "'ll", "n't", "don"]);
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(CliticSplitFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
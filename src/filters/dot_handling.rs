@@ -0,0 +1,329 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'DotHandlingFilter' distinguishes dotted acronyms from domains and file-like names.
+// A token made only of single letters separated by dots, with an optional trailing dot
+// ("U.S.A.", "e.g"), is treated as an acronym and either merged ("USA") or split into its
+// letters ("U", "S", "A") at the acronym's position, depending on the mode.
+// Tokens with longer dot-separated segments ("example.com", "node.js") are treated as
+// domains or names and passed through unchanged.
+// With `merge_initials(true)`, a run of two or more name initials written with spaces
+// ("J. R. R.") that is followed by a capitalized word is merged into a single token at the
+// first initial's position, so "J. R. R. Tolkien" -> "JRR", "Tolkien" ("jrr", "tolkien"
+// after lowercasing). A lone initial is left alone: a capital ending a sentence looks the
+// same ("I got a B. Then we left").
+// Initials and names are recognized from the original text, so the filter can be placed
+// after `LowerCaser` and `OuterPunctuationFilter`. Initials not followed by a name ("Plan
+// B. then") are left as they are.
+
+/// How `DotHandlingFilter` rewrites dotted acronyms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcronymMode {
+    /// "U.S.A." -> "USA"
+    Merge,
+    /// "U.S.A." -> "U", "S", "A"
+    Split,
+}
+
+#[derive(Clone)]
+pub struct DotHandlingFilter {
+    mode: AcronymMode,
+    merge_initials: bool,
+}
+
+impl DotHandlingFilter {
+    /// Creates a `DotHandlingFilter` that rewrites dotted acronyms according to `mode`.
+    pub fn new(mode: AcronymMode) -> DotHandlingFilter {
+        DotHandlingFilter {
+            mode,
+            merge_initials: false,
+        }
+    }
+
+    /// Whether spaced initials followed by a name ("J. R. R. Tolkien") are merged into one
+    /// token ("JRR"). Defaults to false.
+    pub fn merge_initials(mut self, merge: bool) -> DotHandlingFilter {
+        self.merge_initials = merge;
+        self
+    }
+}
+
+impl TokenFilter for DotHandlingFilter {
+    type Tokenizer<T: Tokenizer> = DotHandlingFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> DotHandlingFilterWrapper<T> {
+        DotHandlingFilterWrapper {
+            mode: self.mode,
+            merge_initials: self.merge_initials,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DotHandlingFilterWrapper<T> {
+    mode: AcronymMode,
+    merge_initials: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for DotHandlingFilterWrapper<T> {
+    type TokenStream<'a> = DotHandlingFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        DotHandlingFilterTokenStream {
+            mode: self.mode,
+            merge_initials: self.merge_initials,
+            text,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct DotHandlingFilterTokenStream<'a, T> {
+    mode: AcronymMode,
+    merge_initials: bool,
+    // The original text, used to recognize initials and names.
+    text: &'a str,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+// Returns the letters of a dotted acronym like "U.S.A." or "e.g", or None if the
+// text is not one.
+fn acronym_letters(text: &str) -> Option<Vec<char>> {
+    let body = text.strip_suffix('.').unwrap_or(text);
+    let mut letters = Vec::new();
+    for segment in body.split('.') {
+        let mut chars = segment.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_alphabetic() => letters.push(c),
+            _ => return None,
+        }
+    }
+    (letters.len() >= 2).then_some(letters)
+}
+
+// Checks if text is a single name initial like "J.".
+fn is_initial(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(c), Some('.'), None) if c.is_uppercase()
+    )
+}
+
+// Checks if text is a capitalized word like "Tolkien" or "O'Brien", ignoring trailing
+// sentence punctuation.
+fn is_name(text: &str) -> bool {
+    let text = text.trim_end_matches([',', '.', ';', ':', '!', '?']);
+    text.chars().next().is_some_and(char::is_uppercase)
+        && text.chars().nth(1).is_some()
+        && text
+            .chars()
+            .all(|c| c.is_alphabetic() || c == '-' || c == '\'' || c == '\u{2019}')
+}
+
+impl<T: TokenStream> DotHandlingFilterTokenStream<'_, T> {
+    // The original text of the current tail token.
+    fn source(&self) -> &str {
+        let token = self.tail.token();
+        self.text
+            .get(token.offset_from..token.offset_to)
+            .unwrap_or("")
+    }
+
+    // Collects the run of initials starting at the current tail token and fills
+    // `self.parts`, merging the run if it has at least two initials and is followed by a name.
+    fn handle_initials(&mut self) {
+        let mut run = vec![self.tail.token().clone()];
+        let mut has_next = false;
+        while self.tail.advance() {
+            if !is_initial(self.source()) {
+                has_next = true;
+                break;
+            }
+            run.push(self.tail.token().clone());
+        }
+
+        if has_next && run.len() > 1 && is_name(self.source()) {
+            let last = run[run.len() - 1].offset_to;
+            let letters: String = run
+                .iter()
+                .flat_map(|token| token.text.chars().filter(|c| c.is_alphabetic()))
+                .collect();
+            let merged = Token {
+                text: letters,
+                offset_to: last,
+                ..run[0].clone()
+            };
+            self.parts.push(self.tail.token().clone());
+            self.parts.push(merged);
+            return;
+        }
+
+        if has_next {
+            self.handle();
+            if self.parts.is_empty() {
+                self.parts.push(self.tail.token().clone());
+            }
+        }
+        self.parts.extend(run.into_iter().rev());
+    }
+
+    // Rewrites the current tail token in place (merge) or fills `self.parts` (split).
+    fn handle(&mut self) {
+        let Some(letters) = acronym_letters(&self.tail.token().text) else {
+            return;
+        };
+        match self.mode {
+            AcronymMode::Merge => {
+                let token = self.tail.token_mut();
+                token.text.clear();
+                token.text.extend(letters);
+            }
+            AcronymMode::Split => {
+                let token = self.tail.token();
+                for letter in letters.into_iter().rev() {
+                    self.parts.push(Token {
+                        text: letter.to_string(),
+                        ..token.clone()
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for DotHandlingFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        if self.merge_initials && is_initial(self.source()) {
+            self.handle_initials();
+        } else {
+            self.handle();
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, WhitespaceTokenizer};
+
+    use super::{AcronymMode, DotHandlingFilter};
+
+    #[test]
+    fn test_dot_handling_filter_merge() {
+        let tokens = token_stream_helper("U.S.A. example.com node.js", AcronymMode::Merge);
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "USA", 0, 6);
+        assert_token(&tokens[1], 1, "example.com", 7, 18);
+        assert_token(&tokens[2], 2, "node.js", 19, 26);
+    }
+
+    #[test]
+    fn test_dot_handling_filter_split() {
+        let tokens = token_stream_helper("U.S.A example.com", AcronymMode::Split);
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "U", 0, 5);
+        assert_token(&tokens[1], 0, "S", 0, 5);
+        assert_token(&tokens[2], 0, "A", 0, 5);
+        assert_token(&tokens[3], 1, "example.com", 6, 17);
+    }
+
+    #[test]
+    fn test_dot_handling_filter_non_acronyms() {
+        let tokens = token_stream_helper("a. 1.2.3 U..S", AcronymMode::Merge);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["a.", "1.2.3", "U..S"]);
+    }
+
+    #[test]
+    fn test_dot_handling_filter_merge_initials() {
+        let tokens = token_stream_helper_with(
+            DotHandlingFilter::new(AcronymMode::Merge).merge_initials(true),
+            "J. R. R. Tolkien wrote",
+        );
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "JRR", 0, 8);
+        assert_token(&tokens[1], 3, "Tolkien", 9, 16);
+        assert_token(&tokens[2], 4, "wrote", 17, 22);
+    }
+
+    #[test]
+    fn test_dot_handling_filter_merge_initials_lowercased() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(DotHandlingFilter::new(AcronymMode::Merge).merge_initials(true))
+            .build();
+        let mut token_stream = analyzer.token_stream("by J. R. R. Tolkien.");
+        let mut tokens = vec![];
+        while token_stream.advance() {
+            tokens.push(token_stream.token().text.clone());
+        }
+        assert_eq!(tokens, vec!["by", "jrr", "tolkien"]);
+    }
+
+    #[test]
+    fn test_dot_handling_filter_initials_without_name() {
+        let tokens = token_stream_helper_with(
+            DotHandlingFilter::new(AcronymMode::Merge).merge_initials(true),
+            "Plan B. then A. U.S.A. and C.",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Plan", "B.", "then", "A.", "USA", "and", "C."]);
+    }
+
+    #[test]
+    fn test_dot_handling_filter_lone_initial_before_sentence() {
+        // A capital ending a sentence is not a name initial
+        let tokens = token_stream_helper_with(
+            DotHandlingFilter::new(AcronymMode::Merge).merge_initials(true),
+            "I got a B. Then we left",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["I", "got", "a", "B.", "Then", "we", "left"]);
+    }
+
+    fn token_stream_helper(text: &str, mode: AcronymMode) -> Vec<Token> {
+        token_stream_helper_with(DotHandlingFilter::new(mode), text)
+    }
+
+    fn token_stream_helper_with(filter: DotHandlingFilter, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,121 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'EmojiNormalizationFilter' collapses emoji variants to their base form for aggregation,
+// e.g. "👍🏽" -> "👍" and "❤️" -> "❤".
+// The emoji variation selector (U+FE0F) and the skin-tone modifiers (U+1F3FB to U+1F3FF)
+// are removed wherever they appear in a token. ZWJ sequences keep their zero-width
+// joiners, so each component is normalized and the sequence stays one emoji:
+// "👩🏽‍💻" -> "👩‍💻". A token made only of a modifier is left as it is rather than emptied.
+
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+
+#[derive(Clone)]
+pub struct EmojiNormalizationFilter;
+
+impl TokenFilter for EmojiNormalizationFilter {
+    type Tokenizer<T: Tokenizer> = EmojiNormalizationFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> EmojiNormalizationFilterWrapper<T> {
+        EmojiNormalizationFilterWrapper { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct EmojiNormalizationFilterWrapper<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for EmojiNormalizationFilterWrapper<T> {
+    type TokenStream<'a> = EmojiNormalizationFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        EmojiNormalizationFilterTokenStream {
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct EmojiNormalizationFilterTokenStream<T> {
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+fn is_removed(c: char) -> bool {
+    c == VARIATION_SELECTOR || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+impl<T: TokenStream> TokenStream for EmojiNormalizationFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = &self.tail.token().text;
+        // Fast path: nothing to remove
+        if text.is_ascii() || !text.chars().any(is_removed) {
+            return true;
+        }
+        self.buffer.clear();
+        self.buffer.extend(text.chars().filter(|&c| !is_removed(c)));
+        if self.buffer.is_empty() {
+            return true;
+        }
+        mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::EmojiNormalizationFilter;
+
+    #[test]
+    fn test_emoji_normalization_filter() {
+        let tokens = token_stream_helper("\u{1F44D}\u{1F3FD} \u{1F44D} \u{2764}\u{FE0F}");
+        assert_eq!(tokens.len(), 3);
+        // Skin tone removed, so both thumbs-up collapse to the base emoji
+        assert_token(&tokens[0], 0, "\u{1F44D}", 0, 8);
+        assert_token(&tokens[1], 1, "\u{1F44D}", 9, 13);
+        assert_token(&tokens[2], 2, "\u{2764}", 14, 20);
+    }
+
+    #[test]
+    fn test_emoji_normalization_filter_zwj_sequence() {
+        // Woman technologist with a skin tone: the joiner is kept, the modifier removed
+        let tokens = token_stream_helper("\u{1F469}\u{1F3FD}\u{200D}\u{1F4BB} \u{1F3FD} great");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["\u{1F469}\u{200D}\u{1F4BB}", "\u{1F3FD}", "great"]
+        );
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(EmojiNormalizationFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,257 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'EmphasisPunctuationFilter' keeps the sentiment signal of "!!!" and "???" as marker tokens
+// instead of losing it to punctuation stripping.
+// A standalone run ("!!!") becomes a marker ("<EXCLAIM>"), and a run trailing a word
+// ("great!!!") adds a marker at the word's position after the word itself. Runs of only
+// '!' use the exclaim marker, only '?' the question marker, and mixed runs ("?!") the
+// mixed marker. With intensity buckets enabled the run length is appended to the marker
+// ("<EXCLAIM_3>"), capped at 3.
+// The run is read from the original text, so place this filter after
+// `OuterPunctuationFilter`: that way the punctuation is still seen here but the markers
+// themselves are not trimmed.
+
+/// Runs this long or longer share the top intensity bucket.
+const MAX_INTENSITY: usize = 3;
+
+#[derive(Clone)]
+pub struct EmphasisPunctuationFilter {
+    exclaim: String,
+    question: String,
+    mixed: String,
+    min_run: usize,
+    intensity: bool,
+}
+
+impl EmphasisPunctuationFilter {
+    /// Creates an `EmphasisPunctuationFilter` with the "<EXCLAIM>", "<QUESTION>" and
+    /// "<INTERROBANG>" markers, reacting to runs of any length.
+    pub fn new() -> EmphasisPunctuationFilter {
+        EmphasisPunctuationFilter {
+            exclaim: "<EXCLAIM>".to_string(),
+            question: "<QUESTION>".to_string(),
+            mixed: "<INTERROBANG>".to_string(),
+            min_run: 1,
+            intensity: false,
+        }
+    }
+
+    /// Sets the markers used for runs of '!', runs of '?' and mixed runs.
+    pub fn with_markers(
+        mut self,
+        exclaim: &str,
+        question: &str,
+        mixed: &str,
+    ) -> EmphasisPunctuationFilter {
+        self.exclaim = exclaim.to_string();
+        self.question = question.to_string();
+        self.mixed = mixed.to_string();
+        self
+    }
+
+    /// Only emit markers for runs of at least `min_run` characters.
+    pub fn min_run(mut self, min_run: usize) -> EmphasisPunctuationFilter {
+        self.min_run = min_run.max(1);
+        self
+    }
+
+    /// Append the run length, capped at 3, to the marker ("<EXCLAIM_3>").
+    pub fn intensity_buckets(mut self, intensity: bool) -> EmphasisPunctuationFilter {
+        self.intensity = intensity;
+        self
+    }
+
+    fn marker(&self, run: &str) -> Option<String> {
+        let len = run.chars().count();
+        if len < self.min_run {
+            return None;
+        }
+        let marker = if run.chars().all(|c| c == '!') {
+            &self.exclaim
+        } else if run.chars().all(|c| c == '?') {
+            &self.question
+        } else {
+            &self.mixed
+        };
+        if !self.intensity {
+            return Some(marker.clone());
+        }
+        let bucket = len.min(MAX_INTENSITY);
+        Some(match marker.strip_suffix('>') {
+            Some(name) => format!("{}_{}>", name, bucket),
+            None => format!("{}_{}", marker, bucket),
+        })
+    }
+}
+
+impl Default for EmphasisPunctuationFilter {
+    fn default() -> Self {
+        EmphasisPunctuationFilter::new()
+    }
+}
+
+impl TokenFilter for EmphasisPunctuationFilter {
+    type Tokenizer<T: Tokenizer> = EmphasisPunctuationFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> EmphasisPunctuationFilterWrapper<T> {
+        EmphasisPunctuationFilterWrapper {
+            filter: self,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EmphasisPunctuationFilterWrapper<T> {
+    filter: EmphasisPunctuationFilter,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for EmphasisPunctuationFilterWrapper<T> {
+    type TokenStream<'a> = EmphasisPunctuationFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        EmphasisPunctuationFilterTokenStream {
+            filter: &self.filter,
+            text,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct EmphasisPunctuationFilterTokenStream<'a, T> {
+    filter: &'a EmphasisPunctuationFilter,
+    // The original text, used to find punctuation stripped by earlier filters.
+    text: &'a str,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+fn is_emphasis(c: char) -> bool {
+    c == '!' || c == '?'
+}
+
+// Returns the trailing run of '!' and '?' in `span`, ignoring closing quotes and brackets.
+fn trailing_run(span: &str) -> &str {
+    let span = span.trim_end_matches(|c: char| !is_emphasis(c) && c.is_ascii_punctuation());
+    let start = span
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_emphasis(*c))
+        .last()
+        .map_or(span.len(), |(idx, _)| idx);
+    &span[start..]
+}
+
+impl<T: TokenStream> EmphasisPunctuationFilterTokenStream<'_, T> {
+    fn emphasize(&mut self) {
+        let token = self.tail.token();
+        let span = self
+            .text
+            .get(token.offset_from..token.offset_to)
+            .unwrap_or("");
+        let run = trailing_run(span);
+        let Some(marker) = self.filter.marker(run) else {
+            return;
+        };
+
+        if run.len() == span.len() || token.text.is_empty() || token.text == run {
+            // The token is only punctuation, so it becomes the marker.
+            self.tail.token_mut().text = marker;
+        } else {
+            self.parts.push(Token {
+                text: marker,
+                ..token.clone()
+            });
+            self.parts.push(token.clone());
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for EmphasisPunctuationFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.emphasize();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::EmphasisPunctuationFilter;
+
+    #[test]
+    fn test_emphasis_punctuation_filter_runs() {
+        let tokens = token_stream_helper("!!! ?! ! ???", EmphasisPunctuationFilter::new());
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "<EXCLAIM>", 0, 3);
+        assert_token(&tokens[1], 1, "<INTERROBANG>", 4, 6);
+        assert_token(&tokens[2], 2, "<EXCLAIM>", 7, 8);
+        assert_token(&tokens[3], 3, "<QUESTION>", 9, 12);
+    }
+
+    #[test]
+    fn test_emphasis_punctuation_filter_trailing_run() {
+        let tokens = token_stream_helper("great!!! really?) ok", EmphasisPunctuationFilter::new());
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "great", 0, 8);
+        assert_token(&tokens[1], 0, "<EXCLAIM>", 0, 8);
+        assert_token(&tokens[2], 1, "really", 9, 17);
+        assert_token(&tokens[3], 1, "<QUESTION>", 9, 17);
+        assert_token(&tokens[4], 2, "ok", 18, 20);
+    }
+
+    #[test]
+    fn test_emphasis_punctuation_filter_intensity_and_min_run() {
+        let filter = EmphasisPunctuationFilter::new()
+            .intensity_buckets(true)
+            .min_run(2);
+        let tokens = token_stream_helper("!!! !!!!! ?! !", filter);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // A single "!" is below the minimum run and is stripped as usual
+        assert_eq!(
+            texts,
+            vec!["<EXCLAIM_3>", "<EXCLAIM_3>", "<INTERROBANG_2>", ""]
+        );
+    }
+
+    fn token_stream_helper(text: &str, filter: EmphasisPunctuationFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
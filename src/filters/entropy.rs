@@ -0,0 +1,156 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'EntropyFilter' drops tokens that look randomly generated, such as IDs, hashes and spam
+// strings, by their character-level Shannon entropy in bits.
+// A token's entropy is at most log2 of its length, and natural-language words repeat letters,
+// so even long English words stay well below 4 bits ("internationalization" is about 3.1)
+// while random mixed-case alphanumeric strings of 20 or more characters exceed it.
+// The default threshold of 4.0 bits only drops such strings; lower it to be more aggressive,
+// keeping in mind that short tokens can never exceed log2(length) bits.
+
+/// Default maximum entropy, in bits, of a kept token.
+pub const DEFAULT_MAX_ENTROPY: f64 = 4.0;
+
+#[derive(Clone)]
+pub struct EntropyFilter {
+    max_entropy: f64,
+}
+
+impl EntropyFilter {
+    /// Creates an `EntropyFilter` that drops tokens with more than `max_entropy` bits of
+    /// character entropy.
+    pub fn new(max_entropy: f64) -> EntropyFilter {
+        EntropyFilter { max_entropy }
+    }
+}
+
+impl Default for EntropyFilter {
+    fn default() -> Self {
+        EntropyFilter::new(DEFAULT_MAX_ENTROPY)
+    }
+}
+
+impl TokenFilter for EntropyFilter {
+    type Tokenizer<T: Tokenizer> = EntropyFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> EntropyFilterWrapper<T> {
+        EntropyFilterWrapper {
+            max_entropy: self.max_entropy,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct EntropyFilterWrapper<T> {
+    max_entropy: f64,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for EntropyFilterWrapper<T> {
+    type TokenStream<'a> = EntropyFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        EntropyFilterTokenStream {
+            max_entropy: self.max_entropy,
+            chars: Vec::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct EntropyFilterTokenStream<T> {
+    max_entropy: f64,
+    // chars acts as reusable memory for sorting the characters of each token.
+    chars: Vec<char>,
+    tail: T,
+}
+
+// Returns the Shannon entropy, in bits per character, of `text`.
+// `chars` is scratch memory so that no allocation is needed per token.
+fn shannon_entropy(text: &str, chars: &mut Vec<char>) -> f64 {
+    chars.clear();
+    chars.extend(text.chars());
+    if chars.is_empty() {
+        return 0.0;
+    }
+    chars.sort_unstable();
+
+    let total = chars.len() as f64;
+    chars
+        .chunk_by(|a, b| a == b)
+        .map(|run| {
+            let p = run.len() as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+impl<T: TokenStream> TokenStream for EntropyFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if shannon_entropy(&self.tail.token().text, &mut self.chars) <= self.max_entropy {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::{shannon_entropy, EntropyFilter};
+
+    #[test]
+    fn test_entropy_filter() {
+        let tokens = token_stream_helper(
+            "internationalization aZ3kQ9xLm2PvR7tYwB4n hello",
+            EntropyFilter::default(),
+        );
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "internationalization", 0, 20);
+        // The random-looking token is dropped
+        assert_token(&tokens[1], 2, "hello", 42, 47);
+    }
+
+    #[test]
+    fn test_entropy_filter_threshold() {
+        let tokens = token_stream_helper("aaaa abab abcd", EntropyFilter::new(1.0));
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["aaaa", "abab"]);
+    }
+
+    #[test]
+    fn test_shannon_entropy() {
+        let mut chars = Vec::new();
+        assert_eq!(shannon_entropy("", &mut chars), 0.0);
+        assert_eq!(shannon_entropy("aaaa", &mut chars), 0.0);
+        assert_eq!(shannon_entropy("abab", &mut chars), 1.0);
+        assert_eq!(shannon_entropy("abcd", &mut chars), 2.0);
+    }
+
+    fn token_stream_helper(text: &str, filter: EntropyFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,122 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'ExceptionBodyLowerFilter' lowercases the body of exception tokens, the hashtags and
+// mentions whose leading character is preserved by `OuterPunctuationFilter`, so that
+// "#NewsUpdate" -> "#newsupdate" whatever lowercasing the rest of the pipeline does.
+// The leading run of exception characters is kept as it is and only the remainder of the
+// token is lowercased; tokens that don't start with an exception character are untouched.
+// This makes hashtags consistent in pipelines without `LowerCaser`, or with
+// `SmartLowerCaser`, which keeps all-caps hashtags such as "#NEWS".
+
+#[derive(Clone)]
+pub struct ExceptionBodyLowerFilter {
+    leading_allow: Vec<char>,
+}
+
+impl ExceptionBodyLowerFilter {
+    /// Creates an `ExceptionBodyLowerFilter` for tokens starting with one of `leading_allow`,
+    /// typically the same characters given to `OuterPunctuationFilter::new`.
+    pub fn new(leading_allow: Vec<char>) -> ExceptionBodyLowerFilter {
+        ExceptionBodyLowerFilter { leading_allow }
+    }
+}
+
+impl TokenFilter for ExceptionBodyLowerFilter {
+    type Tokenizer<T: Tokenizer> = ExceptionBodyLowerFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> ExceptionBodyLowerFilterWrapper<T> {
+        ExceptionBodyLowerFilterWrapper {
+            leading_allow: self.leading_allow,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ExceptionBodyLowerFilterWrapper<T> {
+    leading_allow: Vec<char>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for ExceptionBodyLowerFilterWrapper<T> {
+    type TokenStream<'a> = ExceptionBodyLowerFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        ExceptionBodyLowerFilterTokenStream {
+            leading_allow: &self.leading_allow,
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct ExceptionBodyLowerFilterTokenStream<'a, T> {
+    leading_allow: &'a [char],
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for ExceptionBodyLowerFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = &self.tail.token().text;
+        let body = text.trim_start_matches(self.leading_allow);
+        // Fast path: not an exception token, or nothing to lowercase
+        if body.len() == text.len() || !body.chars().any(char::is_uppercase) {
+            return true;
+        }
+        self.buffer.clear();
+        self.buffer.push_str(&text[..text.len() - body.len()]);
+        self.buffer
+            .extend(body.chars().flat_map(char::to_lowercase));
+        mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::ExceptionBodyLowerFilter;
+
+    #[test]
+    fn test_exception_body_lower_filter() {
+        let tokens = token_stream_helper("(#NewsUpdate) @ESA_Live Hello ##TBT");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // The exception characters are preserved, other tokens keep their case
+        assert_eq!(texts, vec!["#newsupdate", "@esa_live", "Hello", "##tbt"]);
+        assert_token(&tokens[0], 0, "#newsupdate", 0, 13);
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(ExceptionBodyLowerFilter::new(vec!['#', '@']))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
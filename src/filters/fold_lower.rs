@@ -0,0 +1,353 @@
+use std::mem;
+use std::sync::OnceLock;
+
+use crate::filters::constants::LATIN_FOLD_LOWER;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, RawTokenizer, TextAnalyzer, Token, TokenFilter, TokenStream,
+};
+
+// 'FoldLowerFilter' removes diacritics and lowercases tokens in a single pass.
+// It replaces `AsciiFoldingFilter` followed by `LowerCaser` on hot paths: each token is
+// walked once and the output is written into a reused buffer.
+// Precomposed Latin-1 Supplement and Latin Extended-A letters are folded from a dense lookup
+// table ("Crème" -> "creme", "Straße" -> "strasse"). Every other character is looked up in a
+// copy of the `AsciiFoldingFilter` table built once on first use ("Việt" -> "viet",
+// "ﬁle" -> "file"), and characters it doesn't fold are only lowercased ("ΑΘΗΝΑ" -> "αθηνα").
+// The output matches the two-filter chain, with one deliberate difference: combining
+// diacritics (U+0300 to U+036F) following a Latin letter are dropped, so decomposed input
+// folds like precomposed input ("e\u{301}" -> "e"), where the chain keeps the mark.
+// Marks following a non-Latin character are kept, so the vowel signs of scripts like
+// Devanagari or Thai are never removed ("हिंदी" is unchanged).
+//
+// `FoldLowerFilter::keep_original` gives a mode that emits the lowercased but unfolded token
+// followed by its folded form at the same position ("Café" -> "café", "cafe"). When folding
+// doesn't change the lowercased token ("Hello" -> "hello") only one token is emitted, so the
+// index doesn't store duplicates.
+
+#[derive(Clone)]
+pub struct FoldLowerFilter;
+
+impl FoldLowerFilter {
+    /// Creates a filter that emits each token's lowercased, unfolded form before its folded form.
+    pub fn keep_original() -> FoldLowerKeepOriginalFilter {
+        FoldLowerKeepOriginalFilter
+    }
+}
+
+impl TokenFilter for FoldLowerFilter {
+    type Tokenizer<T: Tokenizer> = FoldLowerFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> FoldLowerFilterWrapper<T> {
+        FoldLowerFilterWrapper {
+            keep_original: false,
+            inner: tokenizer,
+        }
+    }
+}
+
+/// The keep-original mode of `FoldLowerFilter`.
+#[derive(Clone)]
+pub struct FoldLowerKeepOriginalFilter;
+
+impl TokenFilter for FoldLowerKeepOriginalFilter {
+    type Tokenizer<T: Tokenizer> = FoldLowerFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> FoldLowerFilterWrapper<T> {
+        FoldLowerFilterWrapper {
+            keep_original: true,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FoldLowerFilterWrapper<T> {
+    keep_original: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for FoldLowerFilterWrapper<T> {
+    type TokenStream<'a> = FoldLowerFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        FoldLowerFilterTokenStream {
+            keep_original: self.keep_original,
+            parts: Vec::new(),
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct FoldLowerFilterTokenStream<T> {
+    keep_original: bool,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+fn is_combining_diacritic(c: char) -> bool {
+    ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
+// The characters `AsciiFoldingFilter` changes with their folded forms, sorted by character,
+// and a bitmap of them over the Basic Multilingual Plane so that most non-Latin characters are
+// rejected without a search.
+struct AsciiFolds {
+    bitmap: Vec<u64>,
+    folds: Vec<(char, Box<str>)>,
+}
+
+impl AsciiFolds {
+    fn get(&self, c: char) -> Option<&str> {
+        let i = c as usize;
+        if i > 0xFFFF || self.bitmap[i / 64] & (1 << (i % 64)) == 0 {
+            return None;
+        }
+        self.folds
+            .binary_search_by_key(&c, |&(folded, _)| folded)
+            .ok()
+            .map(|i| &*self.folds[i].1)
+    }
+}
+
+// Builds the `AsciiFoldingFilter` table on first use. The filter folds character by character
+// and its table only covers the Basic Multilingual Plane, so a single pass over every BMP
+// character, separated by NULs, recovers the whole table.
+fn ascii_folds() -> &'static AsciiFolds {
+    static ASCII_FOLDS: OnceLock<AsciiFolds> = OnceLock::new();
+    ASCII_FOLDS.get_or_init(|| {
+        let chars: Vec<char> = ('\u{80}'..='\u{FFFF}').collect();
+        let mut text = String::with_capacity(chars.len() * 4);
+        for &c in &chars {
+            text.push(c);
+            text.push('\0');
+        }
+        let mut folding = TextAnalyzer::builder(RawTokenizer::default())
+            .filter(AsciiFoldingFilter)
+            .build();
+        let mut stream = folding.token_stream(&text);
+        let mut folds: Vec<(char, Box<str>)> = Vec::new();
+        if stream.advance() {
+            folds = chars
+                .iter()
+                .zip(stream.token().text.split('\0'))
+                .filter(|&(&c, folded)| folded.chars().ne([c]))
+                .map(|(&c, folded)| (c, folded.into()))
+                .collect();
+        }
+        let mut bitmap = vec![0; 0x10000 / 64];
+        for &(c, _) in &folds {
+            bitmap[c as usize / 64] |= 1 << (c as usize % 64);
+        }
+        AsciiFolds { bitmap, folds }
+    })
+}
+
+// Folds and lowercases `text` into `output`.
+pub(crate) fn fold_lower(text: &str, output: &mut String) {
+    output.clear();
+    output.reserve(text.len());
+    let folds = ascii_folds();
+    // Whether the last base character was a Latin letter
+    let mut latin_base = false;
+    for c in text.chars() {
+        if c.is_ascii() {
+            output.push(c.to_ascii_lowercase());
+            latin_base = c.is_ascii_alphabetic();
+        } else if ('\u{00C0}'..='\u{017F}').contains(&c) {
+            output.push_str(LATIN_FOLD_LOWER[c as usize - 0xC0]);
+            latin_base = true;
+        } else if is_combining_diacritic(c) {
+            if !latin_base {
+                output.push(c);
+            }
+        } else if let Some(folded) = folds.get(c) {
+            output.extend(folded.chars().map(|c| c.to_ascii_lowercase()));
+            latin_base = folded.ends_with(|c: char| c.is_ascii_alphabetic());
+        } else {
+            output.extend(c.to_lowercase());
+            latin_base = false;
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for FoldLowerFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = &self.tail.token().text;
+        // Fast path: nothing to fold or lowercase
+        if text
+            .bytes()
+            .all(|b| b.is_ascii() && !b.is_ascii_uppercase())
+        {
+            return true;
+        }
+        fold_lower(text, &mut self.buffer);
+        if self.buffer == *text {
+            return true;
+        }
+        let lowercased = self.keep_original.then(|| text.to_lowercase());
+        match lowercased {
+            Some(lowercased) if lowercased != self.buffer => {
+                let token = self.tail.token();
+                self.parts.push(Token {
+                    text: self.buffer.clone(),
+                    ..token.clone()
+                });
+                self.parts.push(Token {
+                    text: lowercased,
+                    ..token.clone()
+                });
+            }
+            _ => mem::swap(&mut self.tail.token_mut().text, &mut self.buffer),
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{
+        AsciiFoldingFilter, LowerCaser, TextAnalyzer, Token, WhitespaceTokenizer,
+    };
+
+    use super::FoldLowerFilter;
+
+    const ACCENTED_WORDS: &str = "Café NAÏVE Crème Brûlée Ångström Straße Œuvre Łódź São Zürich \
+                                  Dvořák Ñandú façade ÆON Þorn żółć ĳssel plain Việt Nguyễn \
+                                  ﬁle Ǆemal ẞ ΑΘΗΝΑ ΟΔΥΣΣΕΥΣ हिंदी ที่นี่ Привет МОСКВА \
+                                  Ｆｕｌｌ ⑴ ½ ǅ";
+    const DECOMPOSED_WORDS: &str = "Cafe\u{0301} NAI\u{0308}VE Vie\u{0323}\u{0302}t Ｅ\u{0301}";
+
+    #[test]
+    fn test_fold_lower_filter() {
+        let tokens = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(FoldLowerFilter)
+                .build(),
+            "Café Straße",
+        );
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "cafe", 0, 5);
+        assert_token(&tokens[1], 1, "strasse", 6, 13);
+    }
+
+    #[test]
+    fn test_fold_lower_filter_matches_folding_then_lowercasing() {
+        let fold_lower = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(FoldLowerFilter)
+                .build(),
+            ACCENTED_WORDS,
+        );
+        let chained = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(AsciiFoldingFilter)
+                .filter(LowerCaser)
+                .build(),
+            ACCENTED_WORDS,
+        );
+        assert_eq!(fold_lower, chained);
+    }
+
+    #[test]
+    fn test_fold_lower_filter_matches_chain_on_decomposed_input() {
+        let fold_lower = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(FoldLowerFilter)
+                .build(),
+            DECOMPOSED_WORDS,
+        );
+        let mut chained = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(AsciiFoldingFilter)
+                .filter(LowerCaser)
+                .build(),
+            DECOMPOSED_WORDS,
+        );
+        // The chain keeps the combining marks that follow Latin letters; nothing else differs
+        for token in &mut chained {
+            token.text = token
+                .text
+                .replace(['\u{0301}', '\u{0302}', '\u{0308}', '\u{0323}'], "");
+        }
+        assert_eq!(fold_lower, chained);
+    }
+
+    #[test]
+    fn test_fold_lower_filter_decomposed_and_non_latin() {
+        let tokens = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(FoldLowerFilter)
+                .build(),
+            "Cafe\u{0301} ΑΘΗΝΑ",
+        );
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "cafe", 0, 6);
+        assert_token(&tokens[1], 1, "αθηνα", 7, 17);
+    }
+
+    #[test]
+    fn test_fold_lower_filter_other_scripts() {
+        let tokens = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(FoldLowerFilter)
+                .build(),
+            "हिंदी ที่นี่ Việt ﬁle",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // Devanagari and Thai vowel signs and tone marks are kept
+        assert_eq!(texts, vec!["हिंदी", "ที่นี่", "viet", "file"]);
+    }
+
+    #[test]
+    fn test_fold_lower_filter_keep_original() {
+        let tokens = token_stream_helper(
+            TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(FoldLowerFilter::keep_original())
+                .build(),
+            "Café cafe \u{3b1}\u{3b8} Hello",
+        );
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "café", 0, 5);
+        assert_token(&tokens[1], 0, "cafe", 0, 5);
+        // Folding doesn't change these once lowercased, so they are not duplicated
+        assert_token(&tokens[2], 1, "cafe", 6, 10);
+        assert_token(&tokens[3], 2, "\u{3b1}\u{3b8}", 11, 15);
+        assert_token(&tokens[4], 3, "hello", 16, 21);
+    }
+
+    fn token_stream_helper(mut analyzer: TextAnalyzer, text: &str) -> Vec<Token> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'HashtagSegmentFilter' splits hashtags like "#blacklivesmatter" into their component
+// words ("black", "lives", "matter") using a word-frequency dictionary.
+// The segmentation with the lowest total cost (the sum of each word's negative log
+// probability) wins, so common words are preferred and fewer, longer words beat many
+// short ones. Dictionary lookups are case-insensitive but the emitted words keep the
+// original casing. If the hashtag body cannot be fully covered by at least two
+// dictionary words, the token is passed through unchanged.
+// Segments are emitted at the same position as the hashtag, optionally alongside it.
+
+#[derive(Clone)]
+pub struct HashtagSegmentFilter {
+    dictionary: Arc<HashMap<String, u64>>,
+    keep_original: bool,
+}
+
+impl HashtagSegmentFilter {
+    /// Creates a `HashtagSegmentFilter` given a map of lowercase words to their frequencies.
+    pub fn new(dictionary: HashMap<String, u64>) -> HashtagSegmentFilter {
+        HashtagSegmentFilter {
+            dictionary: Arc::new(dictionary),
+            keep_original: false,
+        }
+    }
+
+    /// Also emit the original hashtag token before its segments.
+    pub fn keep_original(mut self, keep_original: bool) -> HashtagSegmentFilter {
+        self.keep_original = keep_original;
+        self
+    }
+}
+
+impl TokenFilter for HashtagSegmentFilter {
+    type Tokenizer<T: Tokenizer> = HashtagSegmentFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> HashtagSegmentFilterWrapper<T> {
+        let total = self.dictionary.values().sum::<u64>().max(1) as f64;
+        let max_word_chars = self
+            .dictionary
+            .keys()
+            .map(|word| word.chars().count())
+            .max()
+            .unwrap_or(0);
+        HashtagSegmentFilterWrapper {
+            dictionary: self.dictionary,
+            keep_original: self.keep_original,
+            total,
+            max_word_chars,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HashtagSegmentFilterWrapper<T> {
+    dictionary: Arc<HashMap<String, u64>>,
+    keep_original: bool,
+    total: f64,
+    max_word_chars: usize,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for HashtagSegmentFilterWrapper<T> {
+    type TokenStream<'a> = HashtagSegmentFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        HashtagSegmentFilterTokenStream {
+            segmenter: Segmenter {
+                dictionary: &self.dictionary,
+                total: self.total,
+                max_word_chars: self.max_word_chars,
+            },
+            keep_original: self.keep_original,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+struct Segmenter<'a> {
+    dictionary: &'a HashMap<String, u64>,
+    total: f64,
+    max_word_chars: usize,
+}
+
+impl Segmenter<'_> {
+    // Negative log probability of a dictionary word, or None if it is unknown.
+    fn cost(&self, word: &str) -> Option<f64> {
+        let freq = *self.dictionary.get(&word.to_lowercase())?;
+        Some((self.total / freq.max(1) as f64).ln())
+    }
+
+    // Returns the byte ranges of the cheapest full segmentation of `body`, if any.
+    fn segment(&self, body: &str) -> Option<Vec<(usize, usize)>> {
+        let boundaries: Vec<usize> = body
+            .char_indices()
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(body.len()))
+            .collect();
+        let n = boundaries.len() - 1;
+
+        // best[i] = (cost, previous boundary) of the cheapest segmentation of the first i chars
+        let mut best: Vec<Option<(f64, usize)>> = vec![None; n + 1];
+        best[0] = Some((0.0, 0));
+        for end in 1..=n {
+            for start in end.saturating_sub(self.max_word_chars)..end {
+                let Some((prefix_cost, _)) = best[start] else {
+                    continue;
+                };
+                let word = &body[boundaries[start]..boundaries[end]];
+                if let Some(cost) = self.cost(word) {
+                    let total = prefix_cost + cost;
+                    if best[end].is_none_or(|(current, _)| total < current) {
+                        best[end] = Some((total, start));
+                    }
+                }
+            }
+        }
+
+        best[n]?;
+        let mut ranges = Vec::new();
+        let mut end = n;
+        while end > 0 {
+            let (_, start) = best[end]?;
+            ranges.push((boundaries[start], boundaries[end]));
+            end = start;
+        }
+        ranges.reverse();
+        Some(ranges)
+    }
+}
+
+pub struct HashtagSegmentFilterTokenStream<'a, T> {
+    segmenter: Segmenter<'a>,
+    keep_original: bool,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+impl<T: TokenStream> HashtagSegmentFilterTokenStream<'_, T> {
+    // Fills `self.parts` if the current tail token is a hashtag that can be segmented.
+    fn split(&mut self) {
+        let token = self.tail.token();
+        let Some(body) = token.text.strip_prefix('#') else {
+            return;
+        };
+        let Some(ranges) = self.segmenter.segment(body) else {
+            return;
+        };
+        if ranges.len() < 2 {
+            return;
+        }
+
+        for &(start, end) in ranges.iter().rev() {
+            self.parts.push(Token {
+                text: body[start..end].to_string(),
+                ..token.clone()
+            });
+        }
+        if self.keep_original {
+            self.parts.push(token.clone());
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for HashtagSegmentFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        // Will yield either `self.parts.last()` or
+        // `self.tail.token()` if it could not be split.
+        self.split();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::HashtagSegmentFilter;
+
+    fn dictionary() -> HashMap<String, u64> {
+        [
+            ("black", 500),
+            ("lives", 300),
+            ("live", 400),
+            ("matter", 300),
+            ("mat", 50),
+            ("ter", 5),
+            ("blackli", 1),
+            ("a", 1000),
+        ]
+        .into_iter()
+        .map(|(word, freq)| (word.to_string(), freq))
+        .collect()
+    }
+
+    #[test]
+    fn test_hashtag_segment_filter() {
+        let tokens = token_stream_helper("#blacklivesmatter now", false);
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "black", 0, 17);
+        assert_token(&tokens[1], 0, "lives", 0, 17);
+        assert_token(&tokens[2], 0, "matter", 0, 17);
+        assert_token(&tokens[3], 1, "now", 18, 21);
+    }
+
+    #[test]
+    fn test_hashtag_segment_filter_keep_original_and_case() {
+        let tokens = token_stream_helper("#BlackLivesMatter", true);
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "#BlackLivesMatter", 0, 17);
+        assert_token(&tokens[1], 0, "Black", 0, 17);
+        assert_token(&tokens[2], 0, "Lives", 0, 17);
+        assert_token(&tokens[3], 0, "Matter", 0, 17);
+    }
+
+    #[test]
+    fn test_hashtag_segment_filter_unsegmentable() {
+        let tokens = token_stream_helper("#xyzzy #black blacklivesmatter", true);
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "#xyzzy", 0, 6);
+        // A single dictionary word is not split
+        assert_token(&tokens[1], 1, "#black", 7, 13);
+        // Only hashtags are segmented
+        assert_token(&tokens[2], 2, "blacklivesmatter", 14, 30);
+    }
+
+    fn token_stream_helper(text: &str, keep_original: bool) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(HashtagSegmentFilter::new(dictionary()).keep_original(keep_original))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,201 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'HyphenSplitFilter' splits hyphenated tokens into their segments, e.g. "well-known" ->
+// "well", "known", all at the hyphenated token's position.
+// Tokens with an empty segment ("-5", "e-", "a--b") are left whole.
+// With negation marking enabled, a token whose first segment is a negation ("no", "not",
+// "never") also gets a marker for each following segment: "no-good" -> "no", "good",
+// "NOT_good". This keeps the negated sense of "no-good" or "not-great" for sentiment
+// features while still matching the plain words.
+
+const HYPHENS: [char; 2] = ['-', '\u{2010}'];
+const NEGATIONS: [&str; 3] = ["no", "not", "never"];
+
+#[derive(Clone)]
+pub struct HyphenSplitFilter {
+    // Prefix of the negation markers, or None if negations aren't marked.
+    negation_marker: Option<String>,
+}
+
+impl HyphenSplitFilter {
+    /// Creates a `HyphenSplitFilter` without negation marking.
+    pub fn new() -> HyphenSplitFilter {
+        HyphenSplitFilter {
+            negation_marker: None,
+        }
+    }
+
+    /// Emits a "NOT_" marker for segments following a negation ("no-good" -> "NOT_good").
+    pub fn mark_negations(mut self, mark: bool) -> HyphenSplitFilter {
+        self.negation_marker = mark.then(|| "NOT_".to_string());
+        self
+    }
+
+    /// Sets the prefix used for negation markers and enables negation marking.
+    pub fn negation_marker(mut self, prefix: &str) -> HyphenSplitFilter {
+        self.negation_marker = Some(prefix.to_string());
+        self
+    }
+}
+
+impl Default for HyphenSplitFilter {
+    fn default() -> Self {
+        HyphenSplitFilter::new()
+    }
+}
+
+impl TokenFilter for HyphenSplitFilter {
+    type Tokenizer<T: Tokenizer> = HyphenSplitFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> HyphenSplitFilterWrapper<T> {
+        HyphenSplitFilterWrapper {
+            negation_marker: self.negation_marker,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HyphenSplitFilterWrapper<T> {
+    negation_marker: Option<String>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for HyphenSplitFilterWrapper<T> {
+    type TokenStream<'a> = HyphenSplitFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        HyphenSplitFilterTokenStream {
+            negation_marker: self.negation_marker.as_deref(),
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct HyphenSplitFilterTokenStream<'a, T> {
+    negation_marker: Option<&'a str>,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+fn is_negation(segment: &str) -> bool {
+    NEGATIONS
+        .iter()
+        .any(|negation| negation.eq_ignore_ascii_case(segment))
+}
+
+impl<T: TokenStream> HyphenSplitFilterTokenStream<'_, T> {
+    fn split(&mut self) {
+        let token = self.tail.token();
+        if !token.text.contains(HYPHENS) {
+            return;
+        }
+        let segments: Vec<&str> = token.text.split(HYPHENS).collect();
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return;
+        }
+
+        let mut texts: Vec<String> = segments.iter().map(|s| s.to_string()).collect();
+        if let Some(prefix) = self.negation_marker {
+            if is_negation(segments[0]) {
+                texts.extend(segments[1..].iter().map(|s| format!("{}{}", prefix, s)));
+            }
+        }
+        for text in texts.into_iter().rev() {
+            self.parts.push(Token {
+                text,
+                ..token.clone()
+            });
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for HyphenSplitFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.split();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::HyphenSplitFilter;
+
+    #[test]
+    fn test_hyphen_split_filter() {
+        let tokens = token_stream_helper("well-known -5 e- no-good", HyphenSplitFilter::new());
+        assert_eq!(tokens.len(), 6);
+        assert_token(&tokens[0], 0, "well", 0, 10);
+        assert_token(&tokens[1], 0, "known", 0, 10);
+        // Tokens with empty segments are left whole
+        assert_token(&tokens[2], 1, "-5", 11, 13);
+        assert_token(&tokens[3], 2, "e-", 14, 16);
+        // Without negation marking a negation is split like any other token
+        assert_token(&tokens[4], 3, "no", 17, 24);
+        assert_token(&tokens[5], 3, "good", 17, 24);
+    }
+
+    #[test]
+    fn test_hyphen_split_filter_negation_markers() {
+        let filter = HyphenSplitFilter::new().mark_negations(true);
+        let tokens = token_stream_helper("no-good Not-so-great well-known", filter);
+        assert_eq!(tokens.len(), 10);
+        assert_token(&tokens[0], 0, "no", 0, 7);
+        assert_token(&tokens[1], 0, "good", 0, 7);
+        assert_token(&tokens[2], 0, "NOT_good", 0, 7);
+        assert_token(&tokens[3], 1, "Not", 8, 20);
+        assert_token(&tokens[4], 1, "so", 8, 20);
+        assert_token(&tokens[5], 1, "great", 8, 20);
+        assert_token(&tokens[6], 1, "NOT_so", 8, 20);
+        assert_token(&tokens[7], 1, "NOT_great", 8, 20);
+        assert_token(&tokens[8], 2, "well", 21, 31);
+        assert_token(&tokens[9], 2, "known", 21, 31);
+    }
+
+    #[test]
+    fn test_hyphen_split_filter_custom_negation_marker() {
+        let filter = HyphenSplitFilter::new().negation_marker("neg:");
+        let tokens = token_stream_helper("never\u{2010}again", filter);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["never", "again", "neg:again"]);
+    }
+
+    fn token_stream_helper(text: &str, filter: HyphenSplitFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,195 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'IdentifierSplitFilter' splits source code identifiers into their words on underscores and
+// camelCase boundaries, e.g. "getUserID" -> "get", "User", "ID" and "user_id" -> "user", "id".
+// A camelCase boundary is a lowercase letter or digit followed by an uppercase letter, or the
+// last capital of an uppercase run followed by a lowercase letter ("HTTPServer" -> "HTTP",
+// "Server"). Digits stay attached to the letters before them ("utf8Decode" -> "utf8", "Decode").
+// Case is preserved and leading, trailing or repeated underscores produce no empty words.
+// Words are emitted at the same position as the identifier, optionally alongside it.
+
+#[derive(Clone, Default)]
+pub struct IdentifierSplitFilter {
+    keep_original: bool,
+}
+
+impl IdentifierSplitFilter {
+    /// Creates an `IdentifierSplitFilter` that replaces identifiers with their words.
+    pub fn new() -> IdentifierSplitFilter {
+        IdentifierSplitFilter::default()
+    }
+
+    /// Also emit the original identifier token before its words.
+    pub fn keep_original(mut self, keep_original: bool) -> IdentifierSplitFilter {
+        self.keep_original = keep_original;
+        self
+    }
+}
+
+impl TokenFilter for IdentifierSplitFilter {
+    type Tokenizer<T: Tokenizer> = IdentifierSplitFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> IdentifierSplitFilterWrapper<T> {
+        IdentifierSplitFilterWrapper {
+            keep_original: self.keep_original,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct IdentifierSplitFilterWrapper<T> {
+    keep_original: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for IdentifierSplitFilterWrapper<T> {
+    type TokenStream<'a> = IdentifierSplitFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        IdentifierSplitFilterTokenStream {
+            keep_original: self.keep_original,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct IdentifierSplitFilterTokenStream<T> {
+    keep_original: bool,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+// Returns the words of an identifier, split on underscores and camelCase boundaries.
+fn identifier_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for segment in text.split('_').filter(|segment| !segment.is_empty()) {
+        let chars: Vec<(usize, char)> = segment.char_indices().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let prev = chars[i - 1].1;
+            let cur = chars[i].1;
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+            let boundary = cur.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)));
+            if boundary {
+                words.push(&segment[start..chars[i].0]);
+                start = chars[i].0;
+            }
+        }
+        words.push(&segment[start..]);
+    }
+    words
+}
+
+impl<T: TokenStream> IdentifierSplitFilterTokenStream<T> {
+    // Fills `self.parts` if the current tail token is an identifier with more than one word.
+    fn split(&mut self) {
+        let token = self.tail.token();
+        let words = identifier_words(&token.text);
+        if words.len() < 2 && words.first().is_none_or(|&word| word == token.text) {
+            return;
+        }
+
+        for word in words.into_iter().rev() {
+            self.parts.push(Token {
+                text: word.to_string(),
+                ..token.clone()
+            });
+        }
+        if self.keep_original {
+            self.parts.push(token.clone());
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for IdentifierSplitFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.split();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::{identifier_words, IdentifierSplitFilter};
+
+    #[test]
+    fn test_identifier_words() {
+        assert_eq!(identifier_words("getUserID"), vec!["get", "User", "ID"]);
+        assert_eq!(identifier_words("HTTPServer"), vec!["HTTP", "Server"]);
+        assert_eq!(identifier_words("utf8Decode"), vec!["utf8", "Decode"]);
+        assert_eq!(identifier_words("__init__"), vec!["init"]);
+        assert_eq!(
+            identifier_words("MAX_RETRY_COUNT"),
+            vec!["MAX", "RETRY", "COUNT"]
+        );
+        assert_eq!(identifier_words("plain"), vec!["plain"]);
+    }
+
+    #[test]
+    fn test_identifier_split_filter() {
+        let tokens = token_stream_helper(IdentifierSplitFilter::new(), "getUserID user_id x");
+        assert_eq!(tokens.len(), 6);
+        assert_token(&tokens[0], 0, "get", 0, 9);
+        assert_token(&tokens[1], 0, "User", 0, 9);
+        assert_token(&tokens[2], 0, "ID", 0, 9);
+        assert_token(&tokens[3], 1, "user", 10, 17);
+        assert_token(&tokens[4], 1, "id", 10, 17);
+        assert_token(&tokens[5], 2, "x", 18, 19);
+    }
+
+    #[test]
+    fn test_identifier_split_filter_keep_original() {
+        let tokens = token_stream_helper(
+            IdentifierSplitFilter::new().keep_original(true),
+            "_private parseJSON",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["_private", "private", "parseJSON", "parse", "JSON"]
+        );
+    }
+
+    fn token_stream_helper(filter: IdentifierSplitFilter, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
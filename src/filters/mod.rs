@@ -1,11 +1,73 @@
+pub mod arabic_normalization;
+pub mod bidi_control;
+pub mod char_ngram;
+pub mod clitic_split;
 mod constants;
+pub mod dot_handling;
+pub mod emoji_normalization;
+pub mod emphasis_punctuation;
+pub mod entropy;
+pub mod exception_body_lower;
+pub mod fold_lower;
+pub mod hashtag_segment;
+pub mod hyphen_split;
+pub mod identifier_split;
+pub mod number_unit_merge;
+pub mod number_word;
+pub mod numeric_entity;
+pub mod ocr_normalization;
 pub mod outer_punctuation;
+pub mod path_noise;
+pub mod pii_placeholder;
+pub mod position_gap;
+mod position_restore;
 pub mod possessive_contraction;
+pub mod range_dash;
+pub mod repeated_apostrophe;
+pub mod shared_blocklist;
+pub mod simple_plural;
+pub mod smart_lower_caser;
+mod split_parts;
+pub mod stop_word_mark;
+pub mod truncate_token;
+pub mod version_token;
 
+pub use arabic_normalization::ArabicNormalizationFilter;
+pub use bidi_control::BidiControlFilter;
+pub use char_ngram::CharNgramFilter;
+pub use clitic_split::CliticSplitFilter;
+pub use dot_handling::{AcronymMode, DotHandlingFilter};
+pub use emoji_normalization::EmojiNormalizationFilter;
+pub use emphasis_punctuation::EmphasisPunctuationFilter;
+pub use entropy::EntropyFilter;
+pub use exception_body_lower::ExceptionBodyLowerFilter;
+pub use fold_lower::{FoldLowerFilter, FoldLowerKeepOriginalFilter};
+pub use hashtag_segment::HashtagSegmentFilter;
+pub use hyphen_split::HyphenSplitFilter;
+pub use identifier_split::IdentifierSplitFilter;
+pub use number_unit_merge::NumberUnitMergeFilter;
+pub use number_word::{NumberWordDirection, NumberWordFilter};
+pub use numeric_entity::NumericEntityFilter;
+pub use ocr_normalization::OcrNormalizationFilter;
 pub use outer_punctuation::OuterPunctuationFilter;
-pub use possessive_contraction::PossessiveContractionFilter;
+pub use path_noise::{PathNoiseFilter, PathNoiseMode};
+pub use pii_placeholder::PiiPlaceholderFilter;
+pub use position_gap::PositionGapFilter;
+pub use possessive_contraction::{PossessiveContractionFilter, ProperNounPossessiveFilter};
+pub use range_dash::{RangeDashFilter, RangeDashMode};
+pub use repeated_apostrophe::RepeatedApostropheFilter;
+pub use shared_blocklist::{SharedBlocklist, SharedBlocklistFilter};
+pub use simple_plural::SimplePluralFilter;
+pub use smart_lower_caser::SmartLowerCaser;
+pub use stop_word_mark::StopWordMarkFilter;
+pub use truncate_token::TruncateTokenFilter;
+pub use version_token::VersionTokenFilter;
 
-use constants::STOPWORDS_EN_BASE;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use constants::{STOPWORDS_EN_BASE, STOPWORDS_ES_BASE};
+use tantivy::tokenizer::Language;
 
 /// Unicode apostrophe characters to expand stopwords with.
 const APOSTROPHES: [char; 8] = [
@@ -24,6 +86,14 @@ fn contains_apostrophe(s: &str) -> bool {
     s.chars().any(|c| APOSTROPHES.contains(&c))
 }
 
+/// Check if a string is a plain number, with '.' and ',' allowed between digits ("1,000.5").
+pub(crate) fn is_number(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit())
+        && s.ends_with(|c: char| c.is_ascii_digit())
+        && s.chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+}
+
 /// Replace all apostrophe variants with a specific apostrophe character.
 fn replace_apostrophes(s: &str, replacement: char) -> String {
     s.chars()
@@ -65,6 +135,48 @@ pub fn get_stopwords_filter_en() -> Vec<String> {
     expand_stopwords_with_apostrophe_variants(&STOPWORDS_EN_BASE)
 }
 
+/// Check whether a token is one of Kapiche's English stopwords.
+///
+/// Matches exactly the tokens `kapiche_analyzer_lower_with_stopwords()` removes, including
+/// every apostrophe variant ("don't", "don’t", ...). Matching is case-sensitive, so callers
+/// should lowercase the token first. The expanded stopword set is built once and cached.
+pub fn is_stopword_en(token: &str) -> bool {
+    static STOPWORDS_EN: OnceLock<HashSet<String>> = OnceLock::new();
+    STOPWORDS_EN
+        .get_or_init(|| get_stopwords_filter_en().into_iter().collect())
+        .contains(token)
+}
+
+/// Get the Spanish stopwords list with apostrophe variants expanded.
+pub fn get_stopwords_filter_es() -> Vec<String> {
+    expand_stopwords_with_apostrophe_variants(&STOPWORDS_ES_BASE)
+}
+
+/// Get a combined stopwords list for several languages, with apostrophe variants expanded.
+///
+/// Words shared between languages appear only once, in the order they were first seen.
+/// Languages without a stopword list (currently anything other than English and Spanish)
+/// are ignored.
+pub fn get_stopwords_filter_multi(langs: &[Language]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut combined = Vec::new();
+
+    for lang in langs {
+        let stopwords = match lang {
+            Language::English => get_stopwords_filter_en(),
+            Language::Spanish => get_stopwords_filter_es(),
+            _ => continue,
+        };
+        for word in stopwords {
+            if seen.insert(word.clone()) {
+                combined.push(word);
+            }
+        }
+    }
+
+    combined
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +284,32 @@ mod tests {
         assert!(stopwords.contains(&"can't".to_string())); // U+0027
         assert!(stopwords.contains(&"can't".to_string())); // U+2019
     }
+
+    #[test]
+    fn test_is_stopword_en() {
+        assert!(is_stopword_en("the"));
+        assert!(is_stopword_en("don\u{2019}t"));
+        assert!(!is_stopword_en("elephant"));
+        // Callers are expected to lowercase first
+        assert!(!is_stopword_en("THE"));
+    }
+
+    #[test]
+    fn test_get_stopwords_filter_multi_english_spanish() {
+        let stopwords = get_stopwords_filter_multi(&[Language::English, Language::Spanish]);
+
+        assert!(stopwords.contains(&"the".to_string()));
+        assert!(stopwords.contains(&"el".to_string()));
+
+        // "a" is a stopword in both languages but must only appear once
+        assert_eq!(stopwords.iter().filter(|w| *w == "a").count(), 1);
+        let unique: HashSet<&String> = stopwords.iter().collect();
+        assert_eq!(unique.len(), stopwords.len());
+    }
+
+    #[test]
+    fn test_get_stopwords_filter_multi_ignores_unsupported_languages() {
+        let stopwords = get_stopwords_filter_multi(&[Language::Greek, Language::English]);
+        assert_eq!(stopwords, get_stopwords_filter_en());
+    }
 }
@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::filters::is_number;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'NumberUnitMergeFilter' merges a number followed by a unit into a single token for
+// unit-aware search, e.g. "5 km" -> "5km" and "10 kg" -> "10kg".
+// The merged token takes the number's position and spans both source tokens. A number not
+// followed by one of the configured units is left as it is. Units are matched exactly, so
+// place the filter after `LowerCaser` and give lowercase units for case-insensitive merging.
+// The filter looks one token ahead of each number, so only numbers are buffered.
+
+#[derive(Clone)]
+pub struct NumberUnitMergeFilter {
+    units: Arc<HashSet<String>>,
+}
+
+impl NumberUnitMergeFilter {
+    /// Creates a `NumberUnitMergeFilter` merging numbers with any of `units`.
+    pub fn new<I: IntoIterator<Item = String>>(units: I) -> NumberUnitMergeFilter {
+        NumberUnitMergeFilter {
+            units: Arc::new(units.into_iter().collect()),
+        }
+    }
+}
+
+impl TokenFilter for NumberUnitMergeFilter {
+    type Tokenizer<T: Tokenizer> = NumberUnitMergeFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> NumberUnitMergeFilterWrapper<T> {
+        NumberUnitMergeFilterWrapper {
+            units: self.units,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NumberUnitMergeFilterWrapper<T> {
+    units: Arc<HashSet<String>>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for NumberUnitMergeFilterWrapper<T> {
+    type TokenStream<'a> = NumberUnitMergeFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        NumberUnitMergeFilterTokenStream {
+            units: &self.units,
+            number: None,
+            peeked: false,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct NumberUnitMergeFilterTokenStream<'a, T> {
+    units: &'a HashSet<String>,
+    // The current number token, possibly merged with its unit, while the tail has moved on.
+    number: Option<Token>,
+    // True if the tail holds a token read ahead of `number` that hasn't been emitted yet.
+    peeked: bool,
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for NumberUnitMergeFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        self.number = None;
+        if self.peeked {
+            self.peeked = false;
+        } else if !self.tail.advance() {
+            return false;
+        }
+
+        if !is_number(&self.tail.token().text) {
+            return true;
+        }
+        let mut number = self.tail.token().clone();
+        if self.tail.advance() {
+            let next = self.tail.token();
+            if self.units.contains(&next.text) {
+                number.text.push_str(&next.text);
+                number.offset_to = next.offset_to;
+            } else {
+                self.peeked = true;
+            }
+        }
+        self.number = Some(number);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.number.as_ref().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.number
+            .as_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::NumberUnitMergeFilter;
+
+    #[test]
+    fn test_number_unit_merge_filter() {
+        let tokens = token_stream_helper("ran 5 km in 10 minutes");
+        assert_eq!(tokens.len(), 5);
+        assert_token(&tokens[0], 0, "ran", 0, 3);
+        assert_token(&tokens[1], 1, "5km", 4, 8);
+        assert_token(&tokens[2], 3, "in", 9, 11);
+        // Not a unit, so the number is left split
+        assert_token(&tokens[3], 4, "10", 12, 14);
+        assert_token(&tokens[4], 5, "minutes", 15, 22);
+    }
+
+    #[test]
+    fn test_number_unit_merge_filter_edges() {
+        let tokens = token_stream_helper("2.5 kg 3 4 kg km 7");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // A number read ahead is still checked for its own unit
+        assert_eq!(texts, vec!["2.5kg", "3", "4kg", "km", "7"]);
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let units = vec!["km".to_string(), "kg".to_string()];
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(NumberUnitMergeFilter::new(units))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,269 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'NumberWordFilter' makes small numbers and their English word forms equivalent, converting
+// "3" -> "three" or "twenty" -> "20" depending on the direction.
+// Only 0 to 100 are converted, to keep the lookup bounded: 21 to 99 are written with a
+// hyphen ("twenty-one") and 100 is "hundred". Numbers outside the range, numbers with
+// leading zeros ("007") and other tokens are left as they are. Word forms are matched
+// exactly, so place the filter after `LowerCaser`.
+// With `keep_both(true)` the original token is kept and the converted form is emitted
+// after it at the same position, as a synonym.
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const HUNDRED: &str = "hundred";
+
+/// Which way `NumberWordFilter` converts numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberWordDirection {
+    /// "3" -> "three"
+    ToWords,
+    /// "three" -> "3"
+    ToDigits,
+}
+
+#[derive(Clone)]
+pub struct NumberWordFilter {
+    direction: NumberWordDirection,
+    keep_both: bool,
+}
+
+impl NumberWordFilter {
+    /// Creates a `NumberWordFilter` converting numbers in the given direction.
+    pub fn new(direction: NumberWordDirection) -> NumberWordFilter {
+        NumberWordFilter {
+            direction,
+            keep_both: false,
+        }
+    }
+
+    /// Whether the original token is kept alongside the converted one. Defaults to false.
+    pub fn keep_both(mut self, keep_both: bool) -> NumberWordFilter {
+        self.keep_both = keep_both;
+        self
+    }
+}
+
+impl TokenFilter for NumberWordFilter {
+    type Tokenizer<T: Tokenizer> = NumberWordFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> NumberWordFilterWrapper<T> {
+        NumberWordFilterWrapper {
+            direction: self.direction,
+            keep_both: self.keep_both,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NumberWordFilterWrapper<T> {
+    direction: NumberWordDirection,
+    keep_both: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for NumberWordFilterWrapper<T> {
+    type TokenStream<'a> = NumberWordFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        NumberWordFilterTokenStream {
+            direction: self.direction,
+            keep_both: self.keep_both,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct NumberWordFilterTokenStream<T> {
+    direction: NumberWordDirection,
+    keep_both: bool,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+// Returns the word form of `n`, or None if it is out of range.
+fn number_to_word(n: usize) -> Option<String> {
+    match n {
+        0..=19 => Some(ONES[n].to_string()),
+        20..=99 if n.is_multiple_of(10) => Some(TENS[n / 10 - 2].to_string()),
+        20..=99 => Some(format!("{}-{}", TENS[n / 10 - 2], ONES[n % 10])),
+        100 => Some(HUNDRED.to_string()),
+        _ => None,
+    }
+}
+
+// Returns the number written by `word`, or None if it is not a known word form.
+fn word_to_number(word: &str) -> Option<usize> {
+    if word == HUNDRED {
+        return Some(100);
+    }
+    if let Some(n) = ONES.iter().position(|&one| one == word) {
+        return Some(n);
+    }
+    let (tens, ones) = match word.split_once('-') {
+        Some((tens, ones)) => (tens, Some(ones)),
+        None => (word, None),
+    };
+    let tens = TENS.iter().position(|&ten| ten == tens)? * 10 + 20;
+    match ones {
+        None => Some(tens),
+        Some(ones) => ONES[1..10]
+            .iter()
+            .position(|&one| one == ones)
+            .map(|n| tens + n + 1),
+    }
+}
+
+// Parses a plain decimal number without leading zeros.
+fn parse_number(text: &str) -> Option<usize> {
+    let valid = !text.is_empty()
+        && text.bytes().all(|b| b.is_ascii_digit())
+        && (text == "0" || !text.starts_with('0'));
+    valid.then(|| text.parse().ok()).flatten()
+}
+
+impl<T: TokenStream> NumberWordFilterTokenStream<T> {
+    // Rewrites the current tail token in place or, when keeping both, fills `self.parts`.
+    fn handle(&mut self) {
+        let text = &self.tail.token().text;
+        let converted = match self.direction {
+            NumberWordDirection::ToWords => parse_number(text).and_then(number_to_word),
+            NumberWordDirection::ToDigits => word_to_number(text).map(|n| n.to_string()),
+        };
+        let Some(converted) = converted else {
+            return;
+        };
+        if self.keep_both {
+            let token = self.tail.token();
+            self.parts.push(Token {
+                text: converted,
+                ..token.clone()
+            });
+            self.parts.push(token.clone());
+        } else {
+            self.tail.token_mut().text = converted;
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for NumberWordFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.handle();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::{number_to_word, word_to_number, NumberWordDirection, NumberWordFilter};
+
+    #[test]
+    fn test_number_word_filter_to_words() {
+        let tokens = token_stream_helper(
+            NumberWordFilter::new(NumberWordDirection::ToWords),
+            "3 cats 42 100 101 007",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // Out of range and zero-padded numbers are left alone
+        assert_eq!(
+            texts,
+            vec!["three", "cats", "forty-two", "hundred", "101", "007"]
+        );
+    }
+
+    #[test]
+    fn test_number_word_filter_to_digits() {
+        let tokens = token_stream_helper(
+            NumberWordFilter::new(NumberWordDirection::ToDigits),
+            "twenty twenty-one thousand twenty-zero",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["20", "21", "thousand", "twenty-zero"]);
+    }
+
+    #[test]
+    fn test_number_word_filter_keep_both() {
+        let tokens = token_stream_helper(
+            NumberWordFilter::new(NumberWordDirection::ToWords).keep_both(true),
+            "3 cats",
+        );
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "3", 0, 1);
+        assert_token(&tokens[1], 0, "three", 0, 1);
+        assert_token(&tokens[2], 1, "cats", 2, 6);
+    }
+
+    #[test]
+    fn test_number_word_round_trip() {
+        for n in 0..=100 {
+            let word = number_to_word(n).unwrap();
+            assert_eq!(word_to_number(&word), Some(n), "{}", word);
+        }
+        assert_eq!(number_to_word(101), None);
+    }
+
+    fn token_stream_helper(filter: NumberWordFilter, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,134 @@
+use crate::filters::is_number;
+use crate::filters::split_parts::SplitPartsStream;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'NumericEntityFilter' keeps numbers, decimals, percentages and simple fractions intact,
+// e.g. "12.5%", "3.14", "-2" and "1/2".
+// `OuterPunctuationFilter` strips the "%" from "12.5%" and the sign from "-2", so this
+// filter looks at the token's original text in the source: if it is number-shaped once
+// wrapping punctuation such as brackets, quotes and sentence punctuation is trimmed, the
+// token text is restored to it. Place it after `OuterPunctuationFilter`.
+// Tokens split by an earlier filter such as `HyphenSplitFilter` share the offsets of the
+// unsplit token and are not restored, so the whole token isn't emitted once per part.
+// Other tokens are passed through unchanged.
+
+#[derive(Clone)]
+pub struct NumericEntityFilter;
+
+impl TokenFilter for NumericEntityFilter {
+    type Tokenizer<T: Tokenizer> = NumericEntityFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> NumericEntityFilterWrapper<T> {
+        NumericEntityFilterWrapper { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct NumericEntityFilterWrapper<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for NumericEntityFilterWrapper<T> {
+    type TokenStream<'a> = NumericEntityFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        NumericEntityFilterTokenStream {
+            text,
+            tail: SplitPartsStream::new(self.inner.token_stream(text)),
+        }
+    }
+}
+
+pub struct NumericEntityFilterTokenStream<'a, T> {
+    // The original text, used to recover punctuation stripped by earlier filters.
+    text: &'a str,
+    tail: SplitPartsStream<T>,
+}
+
+// Trims punctuation that can't start or end a numeric entity; letters and digits are kept.
+fn trim_numeric_candidate(span: &str) -> &str {
+    span.trim_start_matches(|c: char| !(c.is_alphanumeric() || c == '+' || c == '-'))
+        .trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '%'))
+}
+
+// Checks if text is a number, decimal, percentage or simple fraction, with an optional sign.
+fn is_numeric_entity(text: &str) -> bool {
+    let body = text.strip_prefix(['+', '-']).unwrap_or(text);
+    match body.split_once('/') {
+        Some((numerator, denominator)) => {
+            is_number(numerator)
+                && !denominator.is_empty()
+                && denominator.chars().all(|c| c.is_ascii_digit())
+        }
+        None => is_number(body.strip_suffix('%').unwrap_or(body)),
+    }
+}
+
+impl<T: TokenStream> TokenStream for NumericEntityFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let token = self.tail.token();
+        let span = self
+            .text
+            .get(token.offset_from..token.offset_to)
+            .unwrap_or("");
+        let candidate = trim_numeric_candidate(span);
+        if candidate != token.text && !self.tail.is_split_part() && is_numeric_entity(candidate) {
+            self.tail.token_mut().text = candidate.to_string();
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::NumericEntityFilter;
+
+    #[test]
+    fn test_numeric_entity_filter() {
+        let tokens = token_stream_helper("12.5% 3.14 1/2 words.");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "12.5%", 0, 5);
+        assert_token(&tokens[1], 1, "3.14", 6, 10);
+        assert_token(&tokens[2], 2, "1/2", 11, 14);
+        // Other tokens are stripped as usual
+        assert_token(&tokens[3], 3, "words", 15, 21);
+    }
+
+    #[test]
+    fn test_numeric_entity_filter_wrapping_punctuation() {
+        let tokens = token_stream_helper("(12.5%), -2 \"1,000\". 50%? 5%off v1.2");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["12.5%", "-2", "1,000", "50%", "5%off", "v1.2"]);
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(NumericEntityFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,240 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'OcrNormalizationFilter' repairs common OCR character confusions.
+// By default it only replaces digits that OCR commonly confuses with letters ('0' -> 'o',
+// '1' -> 'l') inside tokens that are otherwise alphabetic, so "0rder" becomes "order"
+// while numbers like "404" and codes like "h1" or "covid19" are left alone. Tokens ending in
+// a digit are treated as codes ("win10", "lab01") and tokens starting with a number are
+// treated as a count with a unit ("10mins", "101dalmatians"); only a lone leading '0' is
+// repaired, since numbers don't start with zero. Replacement letters take the case of the
+// neighbouring letters ("0RDER" -> "ORDER").
+// Additional substring mappings (e.g. "rn" -> "m") can be supplied; these apply to every
+// token, so only add mappings that are safe for the corpus at hand.
+
+/// Digit to letter confusions applied by default.
+const DEFAULT_DIGIT_CONFUSIONS: [(char, char); 2] = [('0', 'o'), ('1', 'l')];
+
+/// Minimum number of letters a token needs before its digits are treated as OCR errors.
+const MIN_LETTERS: usize = 3;
+
+#[derive(Clone)]
+pub struct OcrNormalizationFilter {
+    mappings: Vec<(String, String)>,
+}
+
+impl OcrNormalizationFilter {
+    /// Creates an `OcrNormalizationFilter` with the conservative default digit rules only.
+    pub fn new() -> OcrNormalizationFilter {
+        OcrNormalizationFilter {
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Adds custom substring mappings, applied in order to every token after the default rules.
+    pub fn with_mappings(mut self, mappings: Vec<(String, String)>) -> OcrNormalizationFilter {
+        self.mappings.extend(mappings);
+        self
+    }
+}
+
+impl Default for OcrNormalizationFilter {
+    fn default() -> Self {
+        OcrNormalizationFilter::new()
+    }
+}
+
+impl TokenFilter for OcrNormalizationFilter {
+    type Tokenizer<T: Tokenizer> = OcrNormalizationFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> OcrNormalizationFilterWrapper<T> {
+        OcrNormalizationFilterWrapper {
+            mappings: self.mappings,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OcrNormalizationFilterWrapper<T> {
+    mappings: Vec<(String, String)>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for OcrNormalizationFilterWrapper<T> {
+    type TokenStream<'a> = OcrNormalizationFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        OcrNormalizationFilterTokenStream {
+            mappings: &self.mappings,
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct OcrNormalizationFilterTokenStream<'a, T> {
+    mappings: &'a [(String, String)],
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+fn confused_letter(c: char) -> Option<char> {
+    DEFAULT_DIGIT_CONFUSIONS
+        .iter()
+        .find(|(digit, _)| *digit == c)
+        .map(|(_, letter)| *letter)
+}
+
+// Returns true if the token is letters plus confusable digits, with enough letters
+// that the digits are more likely OCR errors than part of a number or code. A trailing
+// digit marks an alphanumeric code such as "top10", and a leading number other than a
+// lone '0' marks a count with a unit such as "10mins".
+fn is_confused_word(text: &str) -> bool {
+    if text.ends_with(|c: char| c.is_ascii_digit()) {
+        return false;
+    }
+    let leading_digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    if leading_digits > 1 || (leading_digits == 1 && !text.starts_with('0')) {
+        return false;
+    }
+    let mut letters = 0;
+    let mut digits = 0;
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            letters += 1;
+        } else if confused_letter(c).is_some() {
+            digits += 1;
+        } else {
+            return false;
+        }
+    }
+    digits > 0 && letters >= MIN_LETTERS && digits < letters
+}
+
+// Returns true if the nearest letters before and after `chars[i]` are all uppercase.
+fn is_uppercase_context(chars: &[char], i: usize) -> bool {
+    let before = chars[..i].iter().rev().find(|c| c.is_alphabetic());
+    let after = chars[i + 1..].iter().find(|c| c.is_alphabetic());
+    before.into_iter().chain(after).all(|c| c.is_uppercase())
+}
+
+// Writes the normalized text into output. Returns true if anything changed.
+fn normalize_ocr(text: &str, mappings: &[(String, String)], output: &mut String) -> bool {
+    output.clear();
+    let mut replaced = false;
+
+    if is_confused_word(text) {
+        let chars: Vec<char> = text.chars().collect();
+        for (i, &c) in chars.iter().enumerate() {
+            match confused_letter(c) {
+                Some(letter) if is_uppercase_context(&chars, i) => {
+                    output.push(letter.to_ascii_uppercase())
+                }
+                Some(letter) => output.push(letter),
+                None => output.push(c),
+            }
+        }
+        replaced = true;
+    } else {
+        output.push_str(text);
+    }
+
+    for (from, to) in mappings {
+        if !from.is_empty() && output.contains(from.as_str()) {
+            *output = output.replace(from.as_str(), to);
+            replaced = true;
+        }
+    }
+
+    replaced
+}
+
+impl<T: TokenStream> TokenStream for OcrNormalizationFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        if normalize_ocr(&self.tail.token().text, self.mappings, &mut self.buffer) {
+            mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::OcrNormalizationFilter;
+
+    #[test]
+    fn test_ocr_normalization_filter_digit_confusions() {
+        let tokens = token_stream_helper("0rder he1lo", OcrNormalizationFilter::new());
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "order", 0, 5);
+        assert_token(&tokens[1], 1, "hello", 6, 11);
+    }
+
+    #[test]
+    fn test_ocr_normalization_filter_leaves_numbers_and_codes() {
+        let tokens = token_stream_helper("404 h1 covid19 w00t 10.0", OcrNormalizationFilter::new());
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["404", "h1", "covid19", "w00t", "10.0"]);
+
+        // Trailing digits mark alphanumeric codes, not OCR errors
+        let tokens = token_stream_helper("win10 top10 lab01 WIN10", OcrNormalizationFilter::new());
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["win10", "top10", "lab01", "WIN10"]);
+
+        // Leading numbers are counts with a unit, not OCR errors
+        let tokens = token_stream_helper(
+            "10mins 100days 101dalmatians 1hour",
+            OcrNormalizationFilter::new(),
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["10mins", "100days", "101dalmatians", "1hour"]);
+    }
+
+    #[test]
+    fn test_ocr_normalization_filter_matches_case() {
+        let tokens = token_stream_helper("0RDER H0USE H0use", OcrNormalizationFilter::new());
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["ORDER", "HOUSE", "House"]);
+    }
+
+    #[test]
+    fn test_ocr_normalization_filter_custom_mappings() {
+        let filter =
+            OcrNormalizationFilter::new().with_mappings(vec![("rn".to_string(), "m".to_string())]);
+        let tokens = token_stream_helper("rnodern 0rder", filter);
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "modem", 0, 7);
+        assert_token(&tokens[1], 1, "order", 8, 13);
+    }
+
+    fn token_stream_helper(text: &str, filter: OcrNormalizationFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
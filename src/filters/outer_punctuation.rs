@@ -8,10 +8,18 @@ use unicode_properties::UnicodeGeneralCategory;
 // 'OuterPunctuationFilter' removes any leading or trailing punctuations from tokens.
 // An array of punctuation characters (leading_allow) can be provided
 // to exclude from this filtering process for leading punctuation.
+// Extra leading exceptions can be given for the first token of a field only (position 0),
+// e.g. to keep the opening quote of a quoted subject line while cleaning the other tokens.
+// Tokens made only of combining marks (orphaned diacritics from corrupted text) have no
+// base character to keep, so they are dropped unless `drop_mark_only(false)` is set. This
+// also applies once outer punctuation is ignored, so "(\u{301})" is dropped rather than
+// left empty; tokens made only of punctuation ("...") are still kept as empty tokens.
 
 #[derive(Clone)]
 pub struct OuterPunctuationFilter {
     leading_allow: Vec<char>,
+    first_leading_allow: Vec<char>,
+    drop_mark_only: bool,
 }
 
 impl TokenFilter for OuterPunctuationFilter {
@@ -20,6 +28,8 @@ impl TokenFilter for OuterPunctuationFilter {
     fn transform<T: Tokenizer>(self, tokenizer: T) -> OuterPunctuationFilterWrapper<T> {
         OuterPunctuationFilterWrapper {
             leading_allow: self.leading_allow,
+            first_leading_allow: self.first_leading_allow,
+            drop_mark_only: self.drop_mark_only,
             inner: tokenizer,
         }
     }
@@ -28,13 +38,31 @@ impl TokenFilter for OuterPunctuationFilter {
 impl OuterPunctuationFilter {
     /// Creates a `OuterPunctuationFilter` given an array of exception punctuations.
     pub fn new(leading_allow: Vec<char>) -> OuterPunctuationFilter {
-        OuterPunctuationFilter { leading_allow }
+        OuterPunctuationFilter {
+            leading_allow,
+            first_leading_allow: Vec::new(),
+            drop_mark_only: true,
+        }
+    }
+
+    /// Additional leading punctuation kept on the first token (position 0) only.
+    pub fn first_token_leading_allow(mut self, allow: Vec<char>) -> OuterPunctuationFilter {
+        self.first_leading_allow = allow;
+        self
+    }
+
+    /// Whether tokens made only of combining marks (Mn/Mc/Me) are dropped. Defaults to true.
+    pub fn drop_mark_only(mut self, drop: bool) -> OuterPunctuationFilter {
+        self.drop_mark_only = drop;
+        self
     }
 }
 
 #[derive(Clone)]
 pub struct OuterPunctuationFilterWrapper<T> {
     leading_allow: Vec<char>,
+    first_leading_allow: Vec<char>,
+    drop_mark_only: bool,
     inner: T,
 }
 
@@ -44,6 +72,8 @@ impl<T: Tokenizer> Tokenizer for OuterPunctuationFilterWrapper<T> {
     fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
         OuterPunctuationFilterTokenStream {
             leading_allow: self.leading_allow.clone(),
+            first_leading_allow: self.first_leading_allow.clone(),
+            drop_mark_only: self.drop_mark_only,
             buffer: String::with_capacity(100),
             tail: self.inner.token_stream(text),
         }
@@ -66,8 +96,23 @@ fn is_disallowed_category(c: &char) -> bool {
     )
 }
 
+// Checks if text is made only of combining marks, with no base character, once outer
+// punctuation is ignored.
+fn is_mark_only(text: &str) -> bool {
+    let text = text.trim_matches(|c: char| {
+        c.general_category_group() != GeneralCategoryGroup::Mark
+            && (c.is_ascii_punctuation() || is_disallowed_category(&c))
+    });
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.general_category_group() == GeneralCategoryGroup::Mark)
+}
+
 pub struct OuterPunctuationFilterTokenStream<T> {
     leading_allow: Vec<char>,
+    first_leading_allow: Vec<char>,
+    drop_mark_only: bool,
     // buffer acts as temporary string memory to switch out token text.
     buffer: String,
     tail: T,
@@ -76,16 +121,23 @@ pub struct OuterPunctuationFilterTokenStream<T> {
 // Trims the token stream of any leading/ trailing punctuations.
 impl<T: TokenStream> TokenStream for OuterPunctuationFilterTokenStream<T> {
     fn advance(&mut self) -> bool {
-        if !self.tail.advance() {
-            return false;
+        loop {
+            if !self.tail.advance() {
+                return false;
+            }
+            if !(self.drop_mark_only && is_mark_only(&self.tail.token().text)) {
+                break;
+            }
         }
 
-        let token_text = &self.tail.token().text;
+        let token = self.tail.token();
+        let first = token.position == 0;
 
         // Strip leading punctuation
-        let token_text = token_text.trim_start_matches(|c: char| {
+        let token_text = token.text.trim_start_matches(|c: char| {
             (c.is_ascii_punctuation() || is_disallowed_category(&c))
                 && !self.leading_allow.contains(&c)
+                && !(first && self.first_leading_allow.contains(&c))
         });
 
         // Strip trailing punctuation
@@ -155,6 +207,52 @@ pub mod tests {
         assert_token(&tokens[0], 0, "@#Tree", 0, 10);
     }
 
+    #[test]
+    fn test_to_outer_punctuation_filter_first_token() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(
+                OuterPunctuationFilter::new(vec!['#', '@']).first_token_leading_allow(vec!['"']),
+            )
+            .build();
+        let mut token_stream = analyzer.token_stream("\"Re: \"quoted\" #tag");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        assert_eq!(tokens.len(), 3);
+        // Only the first token keeps its leading quote
+        assert_token(&tokens[0], 0, "\"Re", 0, 4);
+        assert_token(&tokens[1], 1, "quoted", 5, 13);
+        assert_token(&tokens[2], 2, "#tag", 14, 18);
+    }
+
+    #[test]
+    fn test_to_outer_punctuation_filter_mark_only() {
+        // Lone combining accents are dropped; precomposed and decomposed accents are kept
+        let tokens = token_stream_helper("caf\u{e9} \u{301}\u{300} re\u{301}sume");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "caf\u{e9}", 0, 5);
+        assert_token(&tokens[1], 2, "re\u{301}sume", 11, 19);
+
+        // Marks wrapped in punctuation have no base character either
+        let tokens = token_stream_helper("(\u{301}) \"\u{300}\" ok ...");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 2, "ok", 10, 12);
+        // Punctuation-only tokens are kept empty, as before
+        assert_token(&tokens[1], 3, "", 13, 16);
+
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec![]).drop_mark_only(false))
+            .build();
+        let mut token_stream = analyzer.token_stream("\u{301}\u{300}");
+        assert!(token_stream.advance());
+        // Kept, but marks are still stripped as outer characters
+        assert_token(token_stream.token(), 0, "", 0, 4);
+        assert!(!token_stream.advance());
+    }
+
     #[test]
     fn test_to_outer_punctuation_filter_emoji() {
         let tokens = token_stream_helper("🌳");
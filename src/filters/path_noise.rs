@@ -0,0 +1,239 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'PathNoiseFilter' removes file-system noise from code and log corpora: path-like tokens
+// ("/usr/bin", "~/docs", "../src", "C:\Windows", "\\server\share") and bare file extensions
+// (".txt"). Depending on the mode they are dropped or replaced with a "<PATH>" placeholder.
+// File names with a common extension ("readme.txt") are kept unless `drop_filenames(true)`
+// is set. Extensions come from a fixed list that leaves out top-level domains and short
+// language extensions, so domains ("example.com") and names ("node.js") are never touched,
+// and URLs ("https://example.com/a") are not treated as paths.
+// `OuterPunctuationFilter` strips the leading "/" or "." these tokens are recognized by, so
+// the filter looks at the token's original text in the source with wrapping quotes,
+// brackets and sentence punctuation trimmed.
+
+/// Placeholder for path-like tokens in `PathNoiseMode::Placeholder`.
+pub const PATH_PLACEHOLDER: &str = "<PATH>";
+
+// Sorted for binary search.
+const EXTENSIONS: [&str; 46] = [
+    "bak", "bat", "bin", "bmp", "cfg", "conf", "cpp", "csv", "dat", "dll", "doc", "docx", "exe",
+    "gif", "gz", "htm", "html", "ini", "jar", "java", "jpeg", "jpg", "json", "log", "mp3", "mp4",
+    "pdf", "png", "ppt", "pptx", "sql", "svg", "tar", "tgz", "tmp", "toml", "tsv", "txt", "wav",
+    "xls", "xlsx", "xml", "yaml", "yml", "zip", "zst",
+];
+
+const OPENING: [char; 8] = ['"', '\'', '(', '[', '{', '<', '\u{201C}', '\u{2018}'];
+const CLOSING: [char; 14] = [
+    '"', '\'', ')', ']', '}', '>', ',', ';', ':', '.', '!', '?', '\u{201D}', '\u{2019}',
+];
+
+/// What `PathNoiseFilter` does with path-like tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathNoiseMode {
+    /// "/usr/bin" is removed
+    Drop,
+    /// "/usr/bin" -> "<PATH>"
+    Placeholder,
+}
+
+#[derive(Clone)]
+pub struct PathNoiseFilter {
+    mode: PathNoiseMode,
+    drop_filenames: bool,
+}
+
+impl PathNoiseFilter {
+    /// Creates a `PathNoiseFilter` that handles path-like tokens according to `mode`.
+    pub fn new(mode: PathNoiseMode) -> PathNoiseFilter {
+        PathNoiseFilter {
+            mode,
+            drop_filenames: false,
+        }
+    }
+
+    /// Whether file names with a common extension ("readme.txt") are also treated as
+    /// path noise. Defaults to false.
+    pub fn drop_filenames(mut self, drop: bool) -> PathNoiseFilter {
+        self.drop_filenames = drop;
+        self
+    }
+}
+
+impl TokenFilter for PathNoiseFilter {
+    type Tokenizer<T: Tokenizer> = PathNoiseFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> PathNoiseFilterWrapper<T> {
+        PathNoiseFilterWrapper {
+            mode: self.mode,
+            drop_filenames: self.drop_filenames,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PathNoiseFilterWrapper<T> {
+    mode: PathNoiseMode,
+    drop_filenames: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for PathNoiseFilterWrapper<T> {
+    type TokenStream<'a> = PathNoiseFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        PathNoiseFilterTokenStream {
+            mode: self.mode,
+            drop_filenames: self.drop_filenames,
+            text,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct PathNoiseFilterTokenStream<'a, T> {
+    mode: PathNoiseMode,
+    drop_filenames: bool,
+    // The original text, used to recover path characters stripped by earlier filters.
+    text: &'a str,
+    tail: T,
+}
+
+fn is_extension(ext: &str) -> bool {
+    EXTENSIONS
+        .binary_search(&ext.to_ascii_lowercase().as_str())
+        .is_ok()
+}
+
+// Checks if text is a Unix or Windows path.
+fn is_path(text: &str) -> bool {
+    if text.contains("://") || !text.chars().any(char::is_alphanumeric) {
+        return false;
+    }
+    let bytes = text.as_bytes();
+    let windows_drive = bytes.len() > 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    text.starts_with(['/', '\\'])
+        || text.starts_with("~/")
+        || text.starts_with("./")
+        || text.starts_with("../")
+        || windows_drive
+}
+
+// Checks if text is a bare file extension like ".txt".
+fn is_bare_extension(text: &str) -> bool {
+    text.strip_prefix('.').is_some_and(is_extension)
+}
+
+// Checks if text is a file name with a common extension like "readme.txt".
+fn is_filename(text: &str) -> bool {
+    text.rsplit_once('.')
+        .is_some_and(|(name, ext)| !name.is_empty() && is_extension(ext))
+}
+
+impl<T: TokenStream> PathNoiseFilterTokenStream<'_, T> {
+    fn is_noise(&self) -> bool {
+        let token = self.tail.token();
+        let span = self
+            .text
+            .get(token.offset_from..token.offset_to)
+            .unwrap_or("");
+        let candidate = span.trim_start_matches(OPENING).trim_end_matches(CLOSING);
+        is_path(candidate)
+            || is_bare_extension(candidate)
+            || (self.drop_filenames && is_filename(candidate))
+    }
+}
+
+impl<T: TokenStream> TokenStream for PathNoiseFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if !self.is_noise() {
+                return true;
+            }
+            if self.mode == PathNoiseMode::Placeholder {
+                self.tail.token_mut().text = PATH_PLACEHOLDER.to_string();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::{PathNoiseFilter, PathNoiseMode, EXTENSIONS};
+
+    #[test]
+    fn test_path_noise_filter_drop() {
+        let tokens = token_stream_helper(
+            PathNoiseFilter::new(PathNoiseMode::Drop),
+            "see /usr/bin and .txt in readme.txt on example.com",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["see", "and", "in", "readme.txt", "on", "example.com"]
+        );
+        assert_token(&tokens[3], 5, "readme.txt", 25, 35);
+    }
+
+    #[test]
+    fn test_path_noise_filter_drop_filenames() {
+        let tokens = token_stream_helper(
+            PathNoiseFilter::new(PathNoiseMode::Drop).drop_filenames(true),
+            "readme.txt example.com node.js (C:\\Windows) ~/notes.md https://example.com/a",
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["example.com", "node.js", "https://example.com/a"]
+        );
+    }
+
+    #[test]
+    fn test_path_noise_filter_placeholder() {
+        let tokens = token_stream_helper(
+            PathNoiseFilter::new(PathNoiseMode::Placeholder),
+            "\"/var/log/syslog\" .LOG ../src",
+        );
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "<PATH>", 0, 17);
+        assert_token(&tokens[1], 1, "<PATH>", 18, 22);
+        assert_token(&tokens[2], 2, "<PATH>", 23, 29);
+    }
+
+    #[test]
+    fn test_extensions_sorted() {
+        assert!(EXTENSIONS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    fn token_stream_helper(filter: PathNoiseFilter, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,282 @@
+use std::sync::Arc;
+
+use regex::Regex;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'PiiPlaceholderFilter' replaces phone-number and currency tokens with placeholders,
+// e.g. "555-1234" -> "<PHONE>" and "$19.99" -> "<MONEY>".
+// Detection is deliberately conservative so plain numbers, decimals, years and ranges are
+// kept: phone numbers need the usual digit grouping (555.123.4567, (555)123-4567, +61 style
+// prefixes) and amounts need a leading currency symbol. A bare seven-digit local number
+// (555-1234) looks just like a numeric range such as "200-1000", so it is only replaced
+// when the previous token is a phone cue like "call", "tel:" or "phone", and its exchange
+// starts 2-9. "123.4567" and ranges elsewhere in the text are kept.
+// Patterns are matched against the token's original text in the source (with surrounding
+// punctuation trimmed), so the filter still sees "$19.99" when it runs after
+// `OuterPunctuationFilter` has stripped the "$". Run it after punctuation stripping so the
+// placeholders themselves are not trimmed.
+// Additional patterns can be added with `with_pattern`; they are checked after the defaults.
+
+const PHONE_PATTERN: &str = r"^(\+\d{1,3}[-. ]?)?(\(\d{3}\)[-. ]?|\d{3}[-.])\d{3}[-.]\d{4}$|^\+\d{1,3}[-. ]?[2-9]\d{2}-\d{4}$|^\+\d{1,3}[-.]\d{2,4}[-.]\d{3,4}[-.]?\d{0,4}$";
+const LOCAL_PHONE_PATTERN: &str = r"^[2-9]\d{2}-\d{4}$";
+// Words that introduce a local phone number, compared case-insensitively.
+const PHONE_CUES: [&str; 10] = [
+    "call",
+    "cell",
+    "fax",
+    "mob",
+    "mobile",
+    "ph",
+    "phone",
+    "ring",
+    "tel",
+    "telephone",
+];
+const MONEY_PATTERN: &str = r"^[$€£¥]\d{1,3}(,\d{3})*(\.\d{2})?$|^[$€£¥]\d+(\.\d{2})?$";
+
+/// Default placeholder for phone numbers.
+pub const PHONE_PLACEHOLDER: &str = "<PHONE>";
+/// Default placeholder for currency amounts.
+pub const MONEY_PLACEHOLDER: &str = "<MONEY>";
+
+#[derive(Clone)]
+pub struct PiiPlaceholderFilter {
+    patterns: Vec<(Regex, String)>,
+    local_phone: Option<Regex>,
+}
+
+impl PiiPlaceholderFilter {
+    /// Creates a `PiiPlaceholderFilter` with the default phone and currency patterns.
+    pub fn new() -> PiiPlaceholderFilter {
+        PiiPlaceholderFilter {
+            patterns: vec![
+                (
+                    Regex::new(PHONE_PATTERN).expect("valid phone pattern"),
+                    PHONE_PLACEHOLDER.to_string(),
+                ),
+                (
+                    Regex::new(MONEY_PATTERN).expect("valid money pattern"),
+                    MONEY_PLACEHOLDER.to_string(),
+                ),
+            ],
+            local_phone: Some(Regex::new(LOCAL_PHONE_PATTERN).expect("valid local phone pattern")),
+        }
+    }
+
+    /// Creates a `PiiPlaceholderFilter` without any patterns, to be configured with `with_pattern`.
+    pub fn empty() -> PiiPlaceholderFilter {
+        PiiPlaceholderFilter {
+            patterns: Vec::new(),
+            local_phone: None,
+        }
+    }
+
+    /// Adds a pattern whose matching tokens are replaced with `placeholder`.
+    ///
+    /// The pattern should be anchored (`^...$`) to avoid replacing tokens that only
+    /// contain a match.
+    pub fn with_pattern(mut self, pattern: Regex, placeholder: &str) -> PiiPlaceholderFilter {
+        self.patterns.push((pattern, placeholder.to_string()));
+        self
+    }
+}
+
+impl Default for PiiPlaceholderFilter {
+    fn default() -> Self {
+        PiiPlaceholderFilter::new()
+    }
+}
+
+impl TokenFilter for PiiPlaceholderFilter {
+    type Tokenizer<T: Tokenizer> = PiiPlaceholderFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> PiiPlaceholderFilterWrapper<T> {
+        PiiPlaceholderFilterWrapper {
+            patterns: Arc::new(self.patterns),
+            local_phone: self.local_phone,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PiiPlaceholderFilterWrapper<T> {
+    patterns: Arc<Vec<(Regex, String)>>,
+    local_phone: Option<Regex>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for PiiPlaceholderFilterWrapper<T> {
+    type TokenStream<'a> = PiiPlaceholderFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        PiiPlaceholderFilterTokenStream {
+            patterns: &self.patterns,
+            local_phone: self.local_phone.as_ref(),
+            after_phone_cue: false,
+            text,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct PiiPlaceholderFilterTokenStream<'a, T> {
+    patterns: &'a [(Regex, String)],
+    local_phone: Option<&'a Regex>,
+    // Whether the previous token was a phone cue, allowing a local phone number.
+    after_phone_cue: bool,
+    // The original text, used to look at tokens before punctuation was stripped.
+    text: &'a str,
+    tail: T,
+}
+
+// Trims characters that can't start or end a phone number or amount.
+fn trim_pii_candidate(span: &str) -> &str {
+    span.trim_start_matches(|c: char| {
+        !(c.is_ascii_digit() || matches!(c, '+' | '(' | '$' | '€' | '£' | '¥'))
+    })
+    .trim_end_matches(|c: char| !c.is_ascii_digit())
+}
+
+// Checks if a token's source span is a phone cue, ignoring punctuation such as "Tel:".
+fn is_phone_cue(span: &str) -> bool {
+    let word = span.trim_matches(|c: char| !c.is_alphanumeric());
+    PHONE_CUES.iter().any(|cue| cue.eq_ignore_ascii_case(word))
+}
+
+impl<T> PiiPlaceholderFilterTokenStream<'_, T> {
+    fn placeholder(&self, token: &Token) -> Option<&str> {
+        let span = self
+            .text
+            .get(token.offset_from..token.offset_to)
+            .unwrap_or("");
+        let candidates = [token.text.as_str(), trim_pii_candidate(span)];
+        let local_phone = self
+            .local_phone
+            .filter(|_| self.after_phone_cue)
+            .is_some_and(|pattern| candidates.iter().any(|c| pattern.is_match(c)));
+        if local_phone {
+            return Some(PHONE_PLACEHOLDER);
+        }
+        self.patterns
+            .iter()
+            .find(|(pattern, _)| candidates.iter().any(|c| pattern.is_match(c)))
+            .map(|(_, placeholder)| placeholder.as_str())
+    }
+}
+
+impl<T: TokenStream> TokenStream for PiiPlaceholderFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let placeholder = self.placeholder(self.tail.token()).map(str::to_string);
+        let token = self.tail.token();
+        self.after_phone_cue = self
+            .text
+            .get(token.offset_from..token.offset_to)
+            .is_some_and(is_phone_cue);
+        if let Some(placeholder) = placeholder {
+            self.tail.token_mut().text = placeholder;
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use regex::Regex;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::PiiPlaceholderFilter;
+
+    #[test]
+    fn test_pii_placeholder_filter() {
+        let tokens = token_stream_helper(
+            "Call 555-1234 or (555)123-4567, costs $19.99! 42 2020-2024 1,000",
+            PiiPlaceholderFilter::new(),
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Call",
+                "<PHONE>",
+                "or",
+                "<PHONE>",
+                "costs",
+                "<MONEY>",
+                "42",
+                "2020-2024",
+                "1,000"
+            ]
+        );
+        assert_token(&tokens[5], 5, "<MONEY>", 38, 45);
+    }
+
+    #[test]
+    fn test_pii_placeholder_filter_keeps_decimals_and_ranges() {
+        let tokens = token_stream_helper(
+            "123.4567 100-1000 200-1000 500-2000 3.14159 555.1234 call 555.123.4567",
+            PiiPlaceholderFilter::new(),
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // Dotted numbers need an area code to be phone numbers
+        assert_eq!(
+            texts,
+            vec![
+                "123.4567", "100-1000", "200-1000", "500-2000", "3.14159", "555.1234", "call",
+                "<PHONE>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pii_placeholder_filter_local_number_needs_cue() {
+        let tokens = token_stream_helper(
+            "Tel: 555-1234, pages 200-1000 or phone 500-2000 then 555-1234",
+            PiiPlaceholderFilter::new(),
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Tel", "<PHONE>", "pages", "200-1000", "or", "phone", "<PHONE>", "then", "555-1234"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pii_placeholder_filter_custom_pattern() {
+        let filter = PiiPlaceholderFilter::empty()
+            .with_pattern(Regex::new(r"^\d{3}-\d{2}-\d{4}$").unwrap(), "<SSN>");
+        let tokens = token_stream_helper("123-45-6789 555-1234", filter);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["<SSN>", "555-1234"]);
+    }
+
+    fn token_stream_helper(text: &str, filter: PiiPlaceholderFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,194 @@
+use crate::filters::position_restore::PositionRestoreStream;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'PositionGapFilter' replaces the natural position gap left by removed tokens with a fixed
+// gap, e.g. to stop phrase queries matching across removed stopwords regardless of how many
+// were removed. Wherever tokens were dropped between two surviving tokens (or before the
+// first one), exactly `gap` positions are left between them; a gap of 0 renumbers the
+// positions contiguously. Tokens sharing a position keep sharing it, and stopped tokens
+// (position usize::MAX) are passed through and treated as removed.
+// Place the filter after the filters that remove tokens, such as `StopWordFilter`.
+
+#[derive(Clone)]
+pub struct PositionGapFilter {
+    gap: usize,
+}
+
+impl PositionGapFilter {
+    /// Creates a `PositionGapFilter` leaving `gap` positions where tokens were removed.
+    pub fn new(gap: usize) -> PositionGapFilter {
+        PositionGapFilter { gap }
+    }
+}
+
+impl TokenFilter for PositionGapFilter {
+    type Tokenizer<T: Tokenizer> = PositionGapFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> PositionGapFilterWrapper<T> {
+        PositionGapFilterWrapper {
+            gap: self.gap,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PositionGapFilterWrapper<T> {
+    gap: usize,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for PositionGapFilterWrapper<T> {
+    type TokenStream<'a> = PositionGapFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        PositionGapFilterTokenStream {
+            gap: self.gap,
+            last: None,
+            tail: PositionRestoreStream::new(self.inner.token_stream(text)),
+        }
+    }
+}
+
+pub struct PositionGapFilterTokenStream<T> {
+    gap: usize,
+    // The original and rewritten positions of the last surviving token.
+    last: Option<(usize, usize)>,
+    tail: PositionRestoreStream<T>,
+}
+
+impl<T: TokenStream> TokenStream for PositionGapFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let original = self.tail.token().position;
+        if original == usize::MAX {
+            return true;
+        }
+        let position = match self.last {
+            // Same position or the next one: nothing was removed in between
+            Some((last, rewritten)) if original <= last + 1 => rewritten + (original - last),
+            Some((_, rewritten)) => rewritten + 1 + self.gap,
+            None if original == 0 => 0,
+            None => self.gap,
+        };
+        self.last = Some((original, position));
+        self.tail.set_position(position);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::{get_stopwords_filter_en, HyphenSplitFilter, StopWordMarkFilter};
+    use tantivy::tokenizer::{
+        LowerCaser, StopWordFilter, TextAnalyzer, Token, WhitespaceTokenizer,
+    };
+
+    use super::PositionGapFilter;
+
+    #[test]
+    fn test_position_gap_filter() {
+        let tokens = token_stream_helper(10, "cat sat on the mat and then it ran home");
+        let positions: Vec<(&str, usize)> = tokens
+            .iter()
+            .map(|t| (t.text.as_str(), t.position))
+            .collect();
+        // "on the" and "and then it" are removed; each leaves exactly 10 positions
+        assert_eq!(
+            positions,
+            vec![
+                ("cat", 0),
+                ("sat", 1),
+                ("mat", 12),
+                ("ran", 23),
+                ("home", 24)
+            ]
+        );
+        assert_token(&tokens[2], 12, "mat", 15, 18);
+    }
+
+    #[test]
+    fn test_position_gap_filter_zero_gap_and_leading_stopwords() {
+        let tokens = token_stream_helper(0, "the cat sat on the mat");
+        let positions: Vec<usize> = tokens.iter().map(|t| t.position).collect();
+        assert_eq!(positions, vec![0, 1, 2]);
+
+        let tokens = token_stream_helper(5, "the cat");
+        assert_token(&tokens[0], 5, "cat", 4, 7);
+    }
+
+    #[test]
+    fn test_position_gap_filter_marked_stopwords() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(StopWordMarkFilter::new(get_stopwords_filter_en()))
+            .filter(PositionGapFilter::new(3))
+            .build();
+        let mut token_stream = analyzer.token_stream("cat on the mat");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        let positions: Vec<usize> = tokens.iter().map(|t| t.position).collect();
+        assert_eq!(positions, vec![0, usize::MAX, usize::MAX, 4]);
+    }
+
+    #[test]
+    fn test_position_gap_filter_after_splitting() {
+        // Parts keep sharing their position, and positions after them are rewritten from
+        // the restored originals
+        let split_positions = |gap: usize, text: &str| {
+            let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+                .filter(LowerCaser)
+                .filter(StopWordFilter::remove(get_stopwords_filter_en()))
+                .filter(HyphenSplitFilter::new())
+                .filter(PositionGapFilter::new(gap))
+                .build();
+            let mut token_stream = analyzer.token_stream(text);
+            let mut positions = vec![];
+            while token_stream.advance() {
+                positions.push(token_stream.token().position);
+            }
+            positions
+        };
+        // cat, well, known, dog, friendly, parks
+        assert_eq!(
+            split_positions(5, "the cat on well-known dog-friendly parks"),
+            vec![5, 11, 11, 12, 12, 13]
+        );
+        // Without restoring "cat" to position 1, "well-known" would be placed on top of it
+        assert_eq!(
+            split_positions(0, "the cat well-known dog"),
+            vec![0, 1, 1, 2]
+        );
+    }
+
+    fn token_stream_helper(gap: usize, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(StopWordFilter::remove(get_stopwords_filter_en()))
+            .filter(PositionGapFilter::new(gap))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,46 @@
+use tantivy::tokenizer::{Token, TokenStream};
+
+// 'PositionRestoreStream' wraps the tail of a filter that rewrites token positions, such as
+// `PositionGapFilter` and `StopWordMarkFilter`. Tokenizers compute the next token's position
+// from the current token's, so a rewritten position would shift every token after it.
+// `set_position` remembers the original position, and it is written back before the tail
+// advances.
+
+pub(crate) struct PositionRestoreStream<T> {
+    // The original position of the current token, if it was rewritten.
+    original: Option<usize>,
+    tail: T,
+}
+
+impl<T: TokenStream> PositionRestoreStream<T> {
+    pub(crate) fn new(tail: T) -> PositionRestoreStream<T> {
+        PositionRestoreStream {
+            original: None,
+            tail,
+        }
+    }
+
+    // Rewrites the position of the current token until the stream advances.
+    pub(crate) fn set_position(&mut self, position: usize) {
+        let token = self.tail.token_mut();
+        self.original.get_or_insert(token.position);
+        token.position = position;
+    }
+}
+
+impl<T: TokenStream> TokenStream for PositionRestoreStream<T> {
+    fn advance(&mut self) -> bool {
+        if let Some(position) = self.original.take() {
+            self.tail.token_mut().position = position;
+        }
+        self.tail.advance()
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
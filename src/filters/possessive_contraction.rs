@@ -1,26 +1,82 @@
+use std::collections::HashSet;
 use std::mem;
+use std::sync::Arc;
 
-use crate::filters::constants::CONTRACTION_PATTERNS;
+use crate::filters::constants::{CHAIN_CLITICS, CONTRACTION_PATTERNS};
+use crate::filters::APOSTROPHES;
 use tantivy::tokenizer::Tokenizer;
 use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
 
 //    Removes possessive contractions from tokens.
 //    Is fairly robust in that is uses all know unicode apostrophe characters except U+02EE. See
 //    `https://en.wikipedia.org/wiki/Apostrophe#Unicode`_.
+//
+//    A leading apostrophe is never part of a possessive: aphetic forms ("'cause", "'em",
+//    "'tis", "'sup") are left for `OuterPunctuationFilter` to strip, so "'sup" is not
+//    mistaken for a possessive and "'em's" -> "'em".
+//
+//    Tokens with more than one apostrophe are contraction chains ("y'all'd've", "they'd've").
+//    Trailing clitics ('d, 've, 'll, 're, 'm) are dropped from a chain until only its head
+//    contraction remains, so "y'all'd've" -> "y'all" and "they'd've" -> "they'd".
+//    Tokens with a single apostrophe ("y'all", "don't") and chains that don't end in a
+//    known clitic ("rock'n'roll") are left as they are.
+//
+//    `PossessiveContractionFilter::proper_nouns_only` gives a mode that only modifies tokens
+//    that look like proper nouns once the possessive is removed: a capitalized first letter,
+//    or a case-insensitive match in the supplied name gazetteer. "James's" -> "James" but
+//    "boss's" is left as-is. Capitalization is only visible before lowercasing, so place this
+//    mode before `LowerCaser` or rely on the gazetteer. Only the possessive removal depends on
+//    the token's shape: contraction chains are reduced in both modes ("they'd've" -> "they'd").
 
 #[derive(Clone)]
 pub struct PossessiveContractionFilter;
 
+impl PossessiveContractionFilter {
+    /// Creates a filter that only removes possessives from proper nouns.
+    ///
+    /// A token counts as a proper noun if it starts with an uppercase letter or its
+    /// lowercase form is in `names`.
+    pub fn proper_nouns_only<I: IntoIterator<Item = String>>(
+        names: I,
+    ) -> ProperNounPossessiveFilter {
+        ProperNounPossessiveFilter {
+            names: Arc::new(names.into_iter().map(|name| name.to_lowercase()).collect()),
+        }
+    }
+}
+
 impl TokenFilter for PossessiveContractionFilter {
     type Tokenizer<T: Tokenizer> = PossessiveContractionFilterWrapper<T>;
 
     fn transform<T: Tokenizer>(self, tokenizer: T) -> PossessiveContractionFilterWrapper<T> {
-        PossessiveContractionFilterWrapper { inner: tokenizer }
+        PossessiveContractionFilterWrapper {
+            proper_nouns: None,
+            inner: tokenizer,
+        }
+    }
+}
+
+/// The proper-noun-only mode of `PossessiveContractionFilter`.
+#[derive(Clone)]
+pub struct ProperNounPossessiveFilter {
+    names: Arc<HashSet<String>>,
+}
+
+impl TokenFilter for ProperNounPossessiveFilter {
+    type Tokenizer<T: Tokenizer> = PossessiveContractionFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> PossessiveContractionFilterWrapper<T> {
+        PossessiveContractionFilterWrapper {
+            proper_nouns: Some(self.names),
+            inner: tokenizer,
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct PossessiveContractionFilterWrapper<T> {
+    // When set, only proper nouns (capitalized or in this gazetteer) are modified.
+    proper_nouns: Option<Arc<HashSet<String>>>,
     inner: T,
 }
 
@@ -29,6 +85,7 @@ impl<T: Tokenizer> Tokenizer for PossessiveContractionFilterWrapper<T> {
 
     fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
         PossessiveContractionFilterTokenStream {
+            proper_nouns: self.proper_nouns.clone(),
             buffer: String::with_capacity(100),
             tail: self.inner.token_stream(text),
         }
@@ -36,15 +93,73 @@ impl<T: Tokenizer> Tokenizer for PossessiveContractionFilterWrapper<T> {
 }
 
 pub struct PossessiveContractionFilterTokenStream<T> {
+    proper_nouns: Option<Arc<HashSet<String>>>,
     // buffer acts as temporary string memory to switch out token text.
     buffer: String,
     tail: T,
 }
 
+fn is_proper_noun(text: &str, names: &HashSet<String>) -> bool {
+    text.chars().next().is_some_and(char::is_uppercase) || names.contains(&text.to_lowercase())
+}
+
+// Returns the byte length of the head contraction of a contraction chain, dropping
+// trailing clitics while the text still contains more than one apostrophe.
+fn contraction_chain_head_len(text: &str) -> usize {
+    let mut end = text.len();
+    loop {
+        let head = &text[..end];
+        if head.matches(|c: char| APOSTROPHES.contains(&c)).count() < 2 {
+            return end;
+        }
+        let Some((idx, apos)) = head
+            .char_indices()
+            .rev()
+            .find(|(_, c)| APOSTROPHES.contains(c))
+        else {
+            return end;
+        };
+        let clitic = &head[idx + apos.len_utf8()..];
+        if !CHAIN_CLITICS
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(clitic))
+        {
+            return end;
+        }
+        end = idx;
+    }
+}
+
+// Splits a leading apostrophe off text, so "'sup" isn't read as "'s" + "up".
+fn split_leading_apostrophe(text: &str) -> (&str, &str) {
+    let lead_len = text
+        .chars()
+        .next()
+        .filter(|c| APOSTROPHES.contains(c))
+        .map_or(0, char::len_utf8);
+    text.split_at(lead_len)
+}
+
+// Writes text with its contraction chain reduced to the head contraction into the output
+// string, without removing possessives. Returns false if text isn't a reducible chain.
+fn reduce_contraction_chain(text: &str, output: &mut String) -> bool {
+    output.clear();
+    let (lead, text) = split_leading_apostrophe(text);
+    let head_len = contraction_chain_head_len(text);
+    if head_len == text.len() {
+        return false;
+    }
+    output.push_str(lead);
+    output.push_str(&text[..head_len]);
+    true
+}
+
 // Creates desired string with possessive contractions substituted in the output string.
 // Returns True if replacements were made, false otherwise.
 pub fn replace_possessive_contractions(text: &str, output: &mut String) -> bool {
     output.clear();
+    // Keep a leading apostrophe out of the matching
+    let (lead, text) = split_leading_apostrophe(text);
     let mut replaced = false;
     let mut temp = String::from(text);
     for pat in CONTRACTION_PATTERNS {
@@ -53,7 +168,13 @@ pub fn replace_possessive_contractions(text: &str, output: &mut String) -> bool
             replaced = true
         }
     }
+    let head_len = contraction_chain_head_len(&temp);
+    if head_len < temp.len() {
+        temp.truncate(head_len);
+        replaced = true;
+    }
     if replaced {
+        output.push_str(lead);
         output.push_str(&temp);
     }
     replaced
@@ -66,7 +187,17 @@ impl<T: TokenStream> TokenStream for PossessiveContractionFilterTokenStream<T> {
             return false;
         }
         // replace possessive contractions if there are substitutions
-        if replace_possessive_contractions(&self.tail.token().text, &mut self.buffer) {
+        let text = &self.tail.token().text;
+        let replaced = match &self.proper_nouns {
+            None => replace_possessive_contractions(text, &mut self.buffer),
+            // Other tokens keep their possessive but still have chains reduced
+            Some(names) => {
+                (replace_possessive_contractions(text, &mut self.buffer)
+                    && is_proper_noun(&self.buffer, names))
+                    || reduce_contraction_chain(text, &mut self.buffer)
+            }
+        };
+        if replaced {
             mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
         }
         true
@@ -84,7 +215,8 @@ impl<T: TokenStream> TokenStream for PossessiveContractionFilterTokenStream<T> {
 #[cfg(test)]
 mod tests {
     use crate::filters::outer_punctuation::tests::assert_token;
-    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, WhitespaceTokenizer};
 
     use super::PossessiveContractionFilter;
 
@@ -106,10 +238,105 @@ mod tests {
         assert_token(&tokens[0], 0, "Tree", 0, 8);
     }
 
+    #[test]
+    fn test_leading_apostrophe() {
+        let tokens = token_stream_helper("'sup \u{2019}em's 'tis");
+        assert_eq!(tokens.len(), 3);
+        // A leading apostrophe is not a possessive
+        assert_token(&tokens[0], 0, "'sup", 0, 4);
+        assert_token(&tokens[1], 1, "\u{2019}em", 5, 12);
+        assert_token(&tokens[2], 2, "'tis", 13, 17);
+    }
+
+    #[test]
+    fn test_aphetic_forms_with_outer_punctuation() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(PossessiveContractionFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream("'cause 'em 'sup John's");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "cause", 0, 6);
+        assert_token(&tokens[1], 1, "em", 7, 10);
+        assert_token(&tokens[2], 2, "sup", 11, 15);
+        assert_token(&tokens[3], 3, "John", 16, 22);
+    }
+
+    #[test]
+    fn test_contraction_chains() {
+        let tokens =
+            token_stream_helper("y'all y'all'd've they'd've Y\u{2019}ALL\u{2019}D\u{2019}VE");
+        assert_eq!(tokens.len(), 4);
+        // A single contraction is left alone
+        assert_token(&tokens[0], 0, "y'all", 0, 5);
+        // Chains are reduced to their head contraction
+        assert_token(&tokens[1], 1, "y'all", 6, 16);
+        assert_token(&tokens[2], 2, "they'd", 17, 26);
+        assert_token(&tokens[3], 3, "Y\u{2019}ALL", 27, 43);
+    }
+
+    #[test]
+    fn test_contraction_chains_with_possessive_and_unknown_clitics() {
+        let tokens = token_stream_helper("y'all's rock'n'roll shouldn't've");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "y'all", 0, 7);
+        // Not a chain of clitics, so nothing is dropped
+        assert_token(&tokens[1], 1, "rock'n'roll", 8, 19);
+        assert_token(&tokens[2], 2, "shouldn't", 20, 32);
+    }
+
+    #[test]
+    fn test_proper_nouns_only_capitalized_name() {
+        let filter = PossessiveContractionFilter::proper_nouns_only(Vec::new());
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let tokens = collect_tokens(&mut analyzer, "James's boss's");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "James", 0, 7);
+        // A lowercase common noun keeps its possessive
+        assert_token(&tokens[1], 1, "boss's", 8, 14);
+    }
+
+    #[test]
+    fn test_proper_nouns_only_gazetteer_after_lowercasing() {
+        let filter = PossessiveContractionFilter::proper_nouns_only(vec!["James".to_string()]);
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(filter)
+            .build();
+        let tokens = collect_tokens(&mut analyzer, "James\u{2019}s Boss's");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "james", 0, 9);
+        assert_token(&tokens[1], 1, "boss's", 10, 16);
+    }
+
+    #[test]
+    fn test_proper_nouns_only_reduces_chains() {
+        let filter = PossessiveContractionFilter::proper_nouns_only(Vec::new());
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let tokens = collect_tokens(&mut analyzer, "they'd've y'all'd've boss's James's");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        // Chains are reduced regardless of case; only the possessive needs a proper noun
+        assert_eq!(texts, vec!["they'd", "y'all", "boss's", "James"]);
+    }
+
     fn token_stream_helper(text: &str) -> Vec<Token> {
         let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
             .filter(PossessiveContractionFilter)
             .build();
+        collect_tokens(&mut analyzer, text)
+    }
+
+    fn collect_tokens(analyzer: &mut TextAnalyzer, text: &str) -> Vec<Token> {
         let mut token_stream = analyzer.token_stream(text);
         let mut tokens = vec![];
         let mut add_token = |token: &Token| {
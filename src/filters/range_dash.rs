@@ -0,0 +1,191 @@
+use crate::filters::is_number;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'RangeDashFilter' canonicalizes numeric ranges written with a dash, e.g. "2020–2024".
+// Depending on the mode the range is split into its endpoints ("2020", "2024", both at the
+// range's position) or the dash is normalized to ASCII ("2020-2024").
+// Hyphens, figure dashes, en and em dashes and the minus sign are recognized. Only tokens
+// with a number on both sides of a single dash are changed; numbers may contain '.' and ','
+// ("1.5–2.5"). Other dashes ("well–known", "–5") are left for `OuterPunctuationFilter` and
+// other filters.
+
+const DASHES: [char; 6] = [
+    '-',        // hyphen-minus
+    '\u{2010}', // hyphen
+    '\u{2012}', // figure dash
+    '\u{2013}', // en dash
+    '\u{2014}', // em dash
+    '\u{2212}', // minus sign
+];
+
+/// How `RangeDashFilter` rewrites numeric ranges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeDashMode {
+    /// "2020–2024" -> "2020", "2024"
+    Split,
+    /// "2020–2024" -> "2020-2024"
+    Normalize,
+}
+
+#[derive(Clone)]
+pub struct RangeDashFilter {
+    mode: RangeDashMode,
+}
+
+impl RangeDashFilter {
+    /// Creates a `RangeDashFilter` that rewrites numeric ranges according to `mode`.
+    pub fn new(mode: RangeDashMode) -> RangeDashFilter {
+        RangeDashFilter { mode }
+    }
+}
+
+impl TokenFilter for RangeDashFilter {
+    type Tokenizer<T: Tokenizer> = RangeDashFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> RangeDashFilterWrapper<T> {
+        RangeDashFilterWrapper {
+            mode: self.mode,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RangeDashFilterWrapper<T> {
+    mode: RangeDashMode,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for RangeDashFilterWrapper<T> {
+    type TokenStream<'a> = RangeDashFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        RangeDashFilterTokenStream {
+            mode: self.mode,
+            parts: Vec::new(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct RangeDashFilterTokenStream<T> {
+    mode: RangeDashMode,
+    // Pending tokens in reverse order, so that `parts.pop()` yields them in order.
+    parts: Vec<Token>,
+    tail: T,
+}
+
+// Returns the endpoints of a numeric range like "2020–2024", or None if the text is not one.
+fn range_endpoints(text: &str) -> Option<(&str, &str)> {
+    let (idx, dash) = text.char_indices().find(|(_, c)| DASHES.contains(c))?;
+    let (start, end) = (&text[..idx], &text[idx + dash.len_utf8()..]);
+    (is_number(start) && is_number(end)).then_some((start, end))
+}
+
+impl<T: TokenStream> RangeDashFilterTokenStream<T> {
+    // Rewrites the current tail token in place (normalize) or fills `self.parts` (split).
+    fn handle(&mut self) {
+        let token = self.tail.token();
+        let Some((start, end)) = range_endpoints(&token.text) else {
+            return;
+        };
+        match self.mode {
+            RangeDashMode::Normalize => {
+                let normalized = format!("{}-{}", start, end);
+                self.tail.token_mut().text = normalized;
+            }
+            RangeDashMode::Split => {
+                let parts = [end.to_string(), start.to_string()].map(|text| Token {
+                    text,
+                    ..token.clone()
+                });
+                self.parts.extend(parts);
+            }
+        }
+    }
+}
+
+impl<T: TokenStream> TokenStream for RangeDashFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        self.parts.pop();
+
+        if !self.parts.is_empty() {
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.handle();
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.parts.last().unwrap_or_else(|| self.tail.token())
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.parts
+            .last_mut()
+            .unwrap_or_else(|| self.tail.token_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::{RangeDashFilter, RangeDashMode};
+
+    #[test]
+    fn test_range_dash_filter_split() {
+        let tokens = token_stream_helper("2020\u{2013}2024 1.5-2.5", RangeDashMode::Split);
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "2020", 0, 11);
+        assert_token(&tokens[1], 0, "2024", 0, 11);
+        assert_token(&tokens[2], 1, "1.5", 12, 19);
+        assert_token(&tokens[3], 1, "2.5", 12, 19);
+    }
+
+    #[test]
+    fn test_range_dash_filter_normalize() {
+        let tokens = token_stream_helper("2020\u{2013}2024 10\u{2014}20", RangeDashMode::Normalize);
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 0, "2020-2024", 0, 11);
+        assert_token(&tokens[1], 1, "10-20", 12, 19);
+    }
+
+    #[test]
+    fn test_range_dash_filter_non_numeric() {
+        let tokens = token_stream_helper(
+            "well\u{2013}known \u{2013}5 2020\u{2013} 1\u{2013}2\u{2013}3",
+            RangeDashMode::Split,
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "well\u{2013}known",
+                "\u{2013}5",
+                "2020\u{2013}",
+                "1\u{2013}2\u{2013}3"
+            ]
+        );
+    }
+
+    fn token_stream_helper(text: &str, mode: RangeDashMode) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(RangeDashFilter::new(mode))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,163 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+use crate::filters::APOSTROPHES;
+
+// 'RepeatedApostropheFilter' repairs apostrophe artifacts from bad exports, where a single
+// apostrophe comes out doubled ("don''t") or split by zero-width characters ("O'\u{200B}'Brien").
+// A run of two or more apostrophe variants, possibly separated by zero-width characters, is
+// collapsed to a single ASCII apostrophe. Single apostrophes are left as they are, since the
+// possessive and stopword handling already matches every variant.
+// Place the filter before `PossessiveContractionFilter` and stopword removal, so that
+// "don''t" and "O''Brien's" are recognized.
+
+const ZERO_WIDTH: [char; 5] = [
+    '\u{200B}', // Zero width space
+    '\u{200C}', // Zero width non-joiner
+    '\u{200D}', // Zero width joiner
+    '\u{2060}', // Word joiner
+    '\u{FEFF}', // Zero width no-break space
+];
+
+#[derive(Clone)]
+pub struct RepeatedApostropheFilter;
+
+impl TokenFilter for RepeatedApostropheFilter {
+    type Tokenizer<T: Tokenizer> = RepeatedApostropheFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> RepeatedApostropheFilterWrapper<T> {
+        RepeatedApostropheFilterWrapper { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct RepeatedApostropheFilterWrapper<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for RepeatedApostropheFilterWrapper<T> {
+    type TokenStream<'a> = RepeatedApostropheFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        RepeatedApostropheFilterTokenStream {
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct RepeatedApostropheFilterTokenStream<T> {
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+// Returns the byte length of the apostrophe run starting at the beginning of `text` and the
+// number of apostrophes in it. The run ends at its last apostrophe, so trailing zero-width
+// characters are not part of it.
+fn apostrophe_run(text: &str) -> (usize, usize) {
+    let mut len = 0;
+    let mut count = 0;
+    for (i, c) in text.char_indices() {
+        if APOSTROPHES.contains(&c) {
+            len = i + c.len_utf8();
+            count += 1;
+        } else if !ZERO_WIDTH.contains(&c) {
+            break;
+        }
+    }
+    (len, count)
+}
+
+// Collapses repeated apostrophes in `text` into `buffer`. Returns false if there are none.
+fn collapse_apostrophes(text: &str, buffer: &mut String) -> bool {
+    buffer.clear();
+    let mut changed = false;
+    let mut rest = text;
+    while let Some(start) = rest.find(|c| APOSTROPHES.contains(&c)) {
+        buffer.push_str(&rest[..start]);
+        let (len, count) = apostrophe_run(&rest[start..]);
+        if count > 1 {
+            buffer.push('\'');
+            changed = true;
+        } else {
+            buffer.push_str(&rest[start..start + len]);
+        }
+        rest = &rest[start + len..];
+    }
+    buffer.push_str(rest);
+    changed
+}
+
+impl<T: TokenStream> TokenStream for RepeatedApostropheFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        if collapse_apostrophes(&self.tail.token().text, &mut self.buffer) {
+            mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::PossessiveContractionFilter;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::RepeatedApostropheFilter;
+
+    #[test]
+    fn test_repeated_apostrophe_filter() {
+        let tokens = token_stream_helper("don''t O'\u{200B}'Brien can\u{2019}'t it\u{2019}s");
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "don't", 0, 6);
+        assert_token(&tokens[1], 1, "O'Brien", 7, 18);
+        // Mixed variants collapse too, while a single apostrophe is left alone
+        assert_token(&tokens[2], 2, "can't", 19, 27);
+        assert_token(&tokens[3], 3, "it\u{2019}s", 28, 34);
+    }
+
+    #[test]
+    fn test_repeated_apostrophe_filter_before_possessive() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(RepeatedApostropheFilter)
+            .filter(PossessiveContractionFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream("John''s O'\u{200B}'Brien's");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["John", "O'Brien"]);
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(RepeatedApostropheFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
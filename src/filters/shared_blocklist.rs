@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'SharedBlocklistFilter' removes tokens found in a blocklist that can be changed at runtime.
+// The blocklist is a `SharedBlocklist`, an `Arc<RwLock<Arc<HashSet<String>>>>`: every
+// analyzer built from a clone of the same Arc sees terms added or removed through it, without
+// being rebuilt.
+// Matching is exact, so place the filter after `LowerCaser` and store lowercase terms for
+// case-insensitive blocking.
+//
+// Locking cost: when a token stream is created, the read lock is taken just long enough to
+// clone the inner `Arc`, a snapshot of the set. Each token then only costs a hash lookup, a
+// stream sees a consistent blocklist for its whole document, and writers never wait for
+// in-flight streams. Update the set with `Arc::make_mut`, which copies it only while streams
+// still hold the old snapshot, or swap in a new `Arc`.
+
+/// A blocklist shared between `SharedBlocklistFilter`s and updated at runtime.
+pub type SharedBlocklist = Arc<RwLock<Arc<HashSet<String>>>>;
+
+#[derive(Clone)]
+pub struct SharedBlocklistFilter {
+    blocklist: SharedBlocklist,
+}
+
+impl SharedBlocklistFilter {
+    /// Creates a `SharedBlocklistFilter` backed by the given shared blocklist.
+    pub fn new(blocklist: SharedBlocklist) -> SharedBlocklistFilter {
+        SharedBlocklistFilter { blocklist }
+    }
+
+    /// Creates a `SharedBlocklistFilter` with a new blocklist holding `terms`.
+    ///
+    /// Use `blocklist` to get a handle for updating it.
+    pub fn from_terms<I: IntoIterator<Item = String>>(terms: I) -> SharedBlocklistFilter {
+        SharedBlocklistFilter::new(Arc::new(RwLock::new(Arc::new(terms.into_iter().collect()))))
+    }
+
+    /// Returns the shared blocklist, to update it or to build other filters sharing it.
+    pub fn blocklist(&self) -> SharedBlocklist {
+        self.blocklist.clone()
+    }
+}
+
+impl TokenFilter for SharedBlocklistFilter {
+    type Tokenizer<T: Tokenizer> = SharedBlocklistFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> SharedBlocklistFilterWrapper<T> {
+        SharedBlocklistFilterWrapper {
+            blocklist: self.blocklist,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SharedBlocklistFilterWrapper<T> {
+    blocklist: SharedBlocklist,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for SharedBlocklistFilterWrapper<T> {
+    type TokenStream<'a> = SharedBlocklistFilterTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        SharedBlocklistFilterTokenStream {
+            // A writer that panicked can't leave the set half-updated, so poisoning is ignored.
+            blocklist: self
+                .blocklist
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct SharedBlocklistFilterTokenStream<T> {
+    // Snapshot of the blocklist taken when the stream was created.
+    blocklist: Arc<HashSet<String>>,
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for SharedBlocklistFilterTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if !self.blocklist.contains(&self.tail.token().text) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, WhitespaceTokenizer};
+
+    use super::SharedBlocklistFilter;
+
+    #[test]
+    fn test_shared_blocklist_filter() {
+        let filter = SharedBlocklistFilter::from_terms(vec!["darn".to_string()]);
+        let mut analyzer = analyzer(filter);
+        let tokens = collect_tokens(&mut analyzer, "Darn it all");
+        assert_eq!(tokens.len(), 2);
+        assert_token(&tokens[0], 1, "it", 5, 7);
+        assert_token(&tokens[1], 2, "all", 8, 11);
+    }
+
+    #[test]
+    fn test_shared_blocklist_filter_runtime_update() {
+        let filter = SharedBlocklistFilter::from_terms(Vec::new());
+        let blocklist = filter.blocklist();
+        let mut first = analyzer(filter.clone());
+        let mut second = analyzer(filter);
+
+        let texts = |analyzer: &mut TextAnalyzer, text: &str| -> Vec<String> {
+            collect_tokens(analyzer, text)
+                .into_iter()
+                .map(|t| t.text)
+                .collect()
+        };
+        assert_eq!(texts(&mut second, "heck yes"), vec!["heck", "yes"]);
+
+        // Block a term while a stream is in progress; the update doesn't wait for the stream,
+        // which keeps its snapshot
+        let mut prior = first.token_stream("heck yes");
+        let writer = {
+            let blocklist = blocklist.clone();
+            thread::spawn(move || {
+                Arc::make_mut(&mut blocklist.write().unwrap()).insert("heck".to_string());
+            })
+        };
+        writer.join().unwrap();
+        let mut prior_texts = vec![];
+        while prior.advance() {
+            prior_texts.push(prior.token().text.clone());
+        }
+        drop(prior);
+        assert_eq!(prior_texts, vec!["heck", "yes"]);
+
+        // Both analyzers sharing the blocklist see the change
+        assert_eq!(texts(&mut first, "heck yes"), vec!["yes"]);
+        assert_eq!(texts(&mut second, "Heck yes"), vec!["yes"]);
+
+        Arc::make_mut(&mut blocklist.write().unwrap()).remove("heck");
+        assert_eq!(texts(&mut first, "heck yes"), vec!["heck", "yes"]);
+    }
+
+    #[test]
+    fn test_shared_blocklist_filter_open_streams_dont_block_writers() {
+        let filter = SharedBlocklistFilter::from_terms(vec!["darn".to_string()]);
+        let blocklist = filter.blocklist();
+        let mut first = analyzer(filter.clone());
+        let mut second = analyzer(filter);
+
+        // A writer and a second stream both proceed while a stream is open
+        let mut open = first.token_stream("darn it");
+        *blocklist.write().unwrap() = Arc::new(["it".to_string()].into_iter().collect());
+        let texts: Vec<String> = collect_tokens(&mut second, "darn it")
+            .into_iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(texts, vec!["darn"]);
+
+        assert!(open.advance());
+        assert_eq!(open.token().text, "it");
+        assert!(!open.advance());
+    }
+
+    fn analyzer(filter: SharedBlocklistFilter) -> TextAnalyzer {
+        TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(filter)
+            .build()
+    }
+
+    fn collect_tokens(analyzer: &mut TextAnalyzer, text: &str) -> Vec<Token> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
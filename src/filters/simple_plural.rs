@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'SimplePluralFilter' is a lightweight plural normalizer for pipelines that don't want full
+// Snowball stemming. Only the trailing inflectional "s" of English plurals is removed:
+//   - "ies" -> "y" ("cities" -> "city")
+//   - "es" after ss, x, ch or sh is removed ("boxes" -> "box", "churches" -> "church")
+//   - otherwise a trailing "s" is removed ("cats" -> "cat", "cases" -> "case", "sizes" -> "size")
+// Words ending in "ss", "us" or "is" ("glass", "status", "analysis") are never plurals here,
+// and tokens shorter than the minimum length ("bus", "has") or in the exception list
+// ("news", "series") are left alone. Only alphabetic tokens are changed and suffixes are
+// matched case-insensitively.
+
+/// Tokens shorter than this many characters are never singularized by default.
+pub const DEFAULT_MIN_LEN: usize = 4;
+
+const DEFAULT_EXCEPTIONS: [&str; 12] = [
+    "always",
+    "christmas",
+    "does",
+    "lens",
+    "mathematics",
+    "news",
+    "perhaps",
+    "physics",
+    "series",
+    "species",
+    "whereas",
+    "yes",
+];
+
+#[derive(Clone)]
+pub struct SimplePluralFilter {
+    min_len: usize,
+    exceptions: HashSet<String>,
+}
+
+impl SimplePluralFilter {
+    /// Creates a `SimplePluralFilter` with the default minimum length and exceptions.
+    pub fn new() -> SimplePluralFilter {
+        SimplePluralFilter {
+            min_len: DEFAULT_MIN_LEN,
+            exceptions: DEFAULT_EXCEPTIONS.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    /// Only singularize tokens of at least `min_len` characters.
+    pub fn min_len(mut self, min_len: usize) -> SimplePluralFilter {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Adds words that must never be singularized, matched case-insensitively.
+    pub fn with_exceptions<I: IntoIterator<Item = String>>(
+        mut self,
+        exceptions: I,
+    ) -> SimplePluralFilter {
+        self.exceptions
+            .extend(exceptions.into_iter().map(|e| e.to_lowercase()));
+        self
+    }
+}
+
+impl Default for SimplePluralFilter {
+    fn default() -> Self {
+        SimplePluralFilter::new()
+    }
+}
+
+impl TokenFilter for SimplePluralFilter {
+    type Tokenizer<T: Tokenizer> = SimplePluralFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> SimplePluralFilterWrapper<T> {
+        SimplePluralFilterWrapper {
+            min_len: self.min_len,
+            exceptions: Arc::new(self.exceptions),
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SimplePluralFilterWrapper<T> {
+    min_len: usize,
+    exceptions: Arc<HashSet<String>>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for SimplePluralFilterWrapper<T> {
+    type TokenStream<'a> = SimplePluralFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        SimplePluralFilterTokenStream {
+            min_len: self.min_len,
+            exceptions: &self.exceptions,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct SimplePluralFilterTokenStream<'a, T> {
+    min_len: usize,
+    exceptions: &'a HashSet<String>,
+    tail: T,
+}
+
+fn ends_with_ignore_case(text: &str, suffix: &str) -> bool {
+    text.len() >= suffix.len()
+        && text.is_char_boundary(text.len() - suffix.len())
+        && text[text.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+}
+
+// Returns the byte length of the plural suffix to remove and its replacement, if any.
+fn plural_suffix(text: &str) -> Option<(usize, &'static str)> {
+    if ["ss", "us", "is"]
+        .iter()
+        .any(|suffix| ends_with_ignore_case(text, suffix))
+    {
+        return None;
+    }
+    if ends_with_ignore_case(text, "ies") {
+        // Keep the case of the token for the replacement
+        let y = if text.ends_with("IES") { "Y" } else { "y" };
+        return Some((3, y));
+    }
+    if ["sses", "xes", "ches", "shes"]
+        .iter()
+        .any(|suffix| ends_with_ignore_case(text, suffix))
+    {
+        return Some((2, ""));
+    }
+    if ends_with_ignore_case(text, "s") {
+        return Some((1, ""));
+    }
+    None
+}
+
+impl<T: TokenStream> TokenStream for SimplePluralFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = &self.tail.token().text;
+        if text.chars().count() < self.min_len
+            || !text.chars().all(char::is_alphabetic)
+            || self.exceptions.contains(&text.to_lowercase())
+        {
+            return true;
+        }
+        if let Some((len, replacement)) = plural_suffix(text) {
+            let text = &mut self.tail.token_mut().text;
+            text.truncate(text.len() - len);
+            text.push_str(replacement);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::SimplePluralFilter;
+
+    #[test]
+    fn test_simple_plural_filter() {
+        let tokens = token_stream_helper("cats boxes bus analysis", SimplePluralFilter::new());
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "cat", 0, 4);
+        assert_token(&tokens[1], 1, "box", 5, 10);
+        // Too short
+        assert_token(&tokens[2], 2, "bus", 11, 14);
+        // Not a plural
+        assert_token(&tokens[3], 3, "analysis", 15, 23);
+    }
+
+    #[test]
+    fn test_simple_plural_filter_suffixes() {
+        let tokens = token_stream_helper(
+            "Cities CHURCHES classes cases sizes glass status news 1990s",
+            SimplePluralFilter::new(),
+        );
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["City", "CHURCH", "class", "case", "size", "glass", "status", "news", "1990s"]
+        );
+    }
+
+    #[test]
+    fn test_simple_plural_filter_options() {
+        let filter = SimplePluralFilter::new()
+            .min_len(3)
+            .with_exceptions(vec!["Kapiches".to_string()]);
+        let tokens = token_stream_helper("ups kapiches", filter);
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["up", "kapiches"]);
+    }
+
+    fn token_stream_helper(text: &str, filter: SimplePluralFilter) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,153 @@
+use std::mem;
+
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'SmartLowerCaser' lowercases tokens like `LowerCaser` but preserves all-caps acronyms,
+// so "NASA" stays distinct from "nasa" while "Hello" and "HeLLo" become "hello".
+// A token is an acronym if it has at least two letters and all of them are uppercase
+// ("NASA", "COVID19"); single capitals such as "I" or "A" are lowercased.
+// Hashtags and mentions are matched the same way by default ("#NEWS" is kept), but because
+// they are conventionally case-insensitive, `lowercase_hashtags_always(true)` lowercases
+// every token starting with '#' or '@' and collapses repeated markers ("##NEWS" -> "#news").
+
+const MARKERS: [char; 2] = ['#', '@'];
+
+#[derive(Clone, Default)]
+pub struct SmartLowerCaser {
+    lowercase_hashtags_always: bool,
+}
+
+impl SmartLowerCaser {
+    /// Creates a `SmartLowerCaser` that preserves all-caps acronyms.
+    pub fn new() -> SmartLowerCaser {
+        SmartLowerCaser::default()
+    }
+
+    /// Whether hashtags and mentions are always lowercased, with repeated leading
+    /// markers collapsed to one. Defaults to false.
+    pub fn lowercase_hashtags_always(mut self, always: bool) -> SmartLowerCaser {
+        self.lowercase_hashtags_always = always;
+        self
+    }
+}
+
+impl TokenFilter for SmartLowerCaser {
+    type Tokenizer<T: Tokenizer> = SmartLowerCaserWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> SmartLowerCaserWrapper<T> {
+        SmartLowerCaserWrapper {
+            lowercase_hashtags_always: self.lowercase_hashtags_always,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SmartLowerCaserWrapper<T> {
+    lowercase_hashtags_always: bool,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for SmartLowerCaserWrapper<T> {
+    type TokenStream<'a> = SmartLowerCaserTokenStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        SmartLowerCaserTokenStream {
+            lowercase_hashtags_always: self.lowercase_hashtags_always,
+            buffer: String::with_capacity(100),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct SmartLowerCaserTokenStream<T> {
+    lowercase_hashtags_always: bool,
+    // buffer acts as temporary string memory to switch out token text.
+    buffer: String,
+    tail: T,
+}
+
+// Checks if text has at least two letters and all of them are uppercase.
+fn is_acronym(text: &str) -> bool {
+    let mut letters = text.chars().filter(|c| c.is_alphabetic());
+    letters.clone().nth(1).is_some() && letters.all(char::is_uppercase)
+}
+
+impl<T: TokenStream> TokenStream for SmartLowerCaserTokenStream<T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = &self.tail.token().text;
+        let marker = text.chars().next().filter(|c| MARKERS.contains(c));
+        let text = match marker {
+            // Keep a single marker in front of the body
+            Some(marker) if self.lowercase_hashtags_always => {
+                let body = text.trim_start_matches(marker);
+                &text[text.len() - body.len() - marker.len_utf8()..]
+            }
+            _ if is_acronym(text) => return true,
+            _ => text.as_str(),
+        };
+        self.buffer.clear();
+        self.buffer
+            .extend(text.chars().flat_map(char::to_lowercase));
+        mem::swap(&mut self.tail.token_mut().text, &mut self.buffer);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::SmartLowerCaser;
+
+    #[test]
+    fn test_smart_lower_caser() {
+        let tokens =
+            token_stream_helper(SmartLowerCaser::new(), "NASA Hello HeLLo I COVID19 #NEWS");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["NASA", "hello", "hello", "i", "COVID19", "#NEWS"]
+        );
+    }
+
+    #[test]
+    fn test_smart_lower_caser_lowercase_hashtags_always() {
+        let tokens = token_stream_helper(
+            SmartLowerCaser::new().lowercase_hashtags_always(true),
+            "#NEWS NASA ##News @ESA",
+        );
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], 0, "#news", 0, 5);
+        assert_token(&tokens[1], 1, "NASA", 6, 10);
+        assert_token(&tokens[2], 2, "#news", 11, 17);
+        assert_token(&tokens[3], 3, "@esa", 18, 22);
+    }
+
+    fn token_stream_helper(filter: SmartLowerCaser, text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(filter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
@@ -0,0 +1,75 @@
+use std::mem;
+
+use tantivy::tokenizer::{Token, TokenStream};
+
+// 'SplitPartsStream' wraps the tail of a filter that rewrites tokens from their source span,
+// such as `VersionTokenFilter` and `NumericEntityFilter`. Splitting filters like
+// `HyphenSplitFilter` emit their parts with the offsets of the whole token, so a part's source
+// span is the unsplit token: restoring it would turn every part into the whole token.
+// The stream reads one token ahead so that `is_split_part` can tell whether the current token
+// shares its offsets with the token before or after it. Tokens are copied with `clone_from`,
+// reusing their allocations.
+
+pub(crate) struct SplitPartsStream<T> {
+    current: Token,
+    next: Token,
+    // Whether `current` holds a token that was already returned by `advance`.
+    has_current: bool,
+    // Whether `next` holds the token read ahead from the tail.
+    has_next: bool,
+    // Whether the offsets of `current` are shared with a neighbouring token.
+    split_part: bool,
+    tail: T,
+}
+
+fn same_span(a: &Token, b: &Token) -> bool {
+    a.offset_from == b.offset_from && a.offset_to == b.offset_to
+}
+
+impl<T: TokenStream> SplitPartsStream<T> {
+    pub(crate) fn new(tail: T) -> SplitPartsStream<T> {
+        SplitPartsStream {
+            current: Token::default(),
+            next: Token::default(),
+            has_current: false,
+            has_next: false,
+            split_part: false,
+            tail,
+        }
+    }
+
+    // Returns true if the current token is one of several parts split from the same token.
+    pub(crate) fn is_split_part(&self) -> bool {
+        self.split_part
+    }
+}
+
+impl<T: TokenStream> TokenStream for SplitPartsStream<T> {
+    fn advance(&mut self) -> bool {
+        if self.has_next {
+            mem::swap(&mut self.current, &mut self.next);
+        } else if !self.has_current && self.tail.advance() {
+            self.current.clone_from(self.tail.token());
+        } else {
+            return false;
+        }
+        // `next` now holds the previous token, if there was one
+        let after_previous = self.has_current && same_span(&self.next, &self.current);
+        self.has_current = true;
+
+        self.has_next = self.tail.advance();
+        if self.has_next {
+            self.next.clone_from(self.tail.token());
+        }
+        self.split_part = after_previous || (self.has_next && same_span(&self.next, &self.current));
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.current
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.current
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::filters::position_restore::PositionRestoreStream;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'StopWordMarkFilter' marks stopwords instead of removing them, for pipelines where the
+// stopword decision is made downstream. Stopwords keep their text and offsets but have
+// their position set to usize::MAX, the convention the utilities in this crate use to
+// recognise stopped tokens; the positions of other tokens are unchanged.
+// Marked tokens are meant for analysis, not indexing: use `StopWordFilter` to remove
+// stopwords from an analyzer used for an index.
+// Matching is exact, so place the filter after `LowerCaser` with a lowercase list such as
+// `get_stopwords_filter_en()`.
+
+#[derive(Clone)]
+pub struct StopWordMarkFilter {
+    words: Arc<HashSet<String>>,
+}
+
+impl StopWordMarkFilter {
+    /// Creates a `StopWordMarkFilter` marking any of `words` as stopped.
+    pub fn new<I: IntoIterator<Item = String>>(words: I) -> StopWordMarkFilter {
+        StopWordMarkFilter {
+            words: Arc::new(words.into_iter().collect()),
+        }
+    }
+}
+
+impl TokenFilter for StopWordMarkFilter {
+    type Tokenizer<T: Tokenizer> = StopWordMarkFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> StopWordMarkFilterWrapper<T> {
+        StopWordMarkFilterWrapper {
+            words: self.words,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StopWordMarkFilterWrapper<T> {
+    words: Arc<HashSet<String>>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for StopWordMarkFilterWrapper<T> {
+    type TokenStream<'a> = StopWordMarkFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        StopWordMarkFilterTokenStream {
+            words: &self.words,
+            tail: PositionRestoreStream::new(self.inner.token_stream(text)),
+        }
+    }
+}
+
+pub struct StopWordMarkFilterTokenStream<'a, T> {
+    words: &'a HashSet<String>,
+    tail: PositionRestoreStream<T>,
+}
+
+impl<T: TokenStream> TokenStream for StopWordMarkFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        if self.words.contains(&self.tail.token().text) {
+            self.tail.set_position(usize::MAX);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::get_stopwords_filter_en;
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, WhitespaceTokenizer};
+
+    use super::StopWordMarkFilter;
+
+    #[test]
+    fn test_stop_word_mark_filter() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(StopWordMarkFilter::new(get_stopwords_filter_en()))
+            .build();
+        let mut token_stream = analyzer.token_stream("The cat and dog");
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        assert_eq!(tokens.len(), 4);
+        assert_token(&tokens[0], usize::MAX, "the", 0, 3);
+        assert_token(&tokens[1], 1, "cat", 4, 7);
+        assert_token(&tokens[2], usize::MAX, "and", 8, 11);
+        assert_token(&tokens[3], 3, "dog", 12, 15);
+    }
+}
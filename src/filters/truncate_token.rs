@@ -0,0 +1,215 @@
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+use unicode_properties::{GeneralCategoryGroup, UnicodeGeneralCategory};
+
+// 'TruncateTokenFilter' shortens tokens to at most `max_chars` grapheme clusters.
+// It is the truncating counterpart to tantivy's `RemoveLongFilter`, which drops
+// long tokens entirely. The downside is that a truncated token is a prefix that
+// may match unrelated words (e.g. "internationalization" truncated to 8 becomes
+// "internat"), so only use it where keeping some signal beats losing the token.
+// Offsets are shrunk to the kept prefix when the token text can be found in its source
+// span, which also works after `OuterPunctuationFilter` has stripped a leading quote.
+// Otherwise, e.g. after `LowerCaser`, they are left covering the whole original token.
+
+#[derive(Clone)]
+pub struct TruncateTokenFilter {
+    max_chars: usize,
+}
+
+impl TruncateTokenFilter {
+    /// Creates a `TruncateTokenFilter` keeping at most `max_chars` grapheme clusters per token.
+    pub fn new(max_chars: usize) -> TruncateTokenFilter {
+        TruncateTokenFilter { max_chars }
+    }
+}
+
+impl TokenFilter for TruncateTokenFilter {
+    type Tokenizer<T: Tokenizer> = TruncateTokenFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> TruncateTokenFilterWrapper<T> {
+        TruncateTokenFilterWrapper {
+            max_chars: self.max_chars,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TruncateTokenFilterWrapper<T> {
+    max_chars: usize,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for TruncateTokenFilterWrapper<T> {
+    type TokenStream<'a> = TruncateTokenFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        TruncateTokenFilterTokenStream {
+            max_chars: self.max_chars,
+            text,
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct TruncateTokenFilterTokenStream<'a, T> {
+    max_chars: usize,
+    // The original text, used to locate the kept prefix for the offsets.
+    text: &'a str,
+    tail: T,
+}
+
+fn is_emoji_modifier(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+// Returns true if `c` continues the grapheme cluster started by an earlier character.
+// This approximates extended grapheme clusters: combining marks, variation selectors,
+// skin-tone modifiers, ZWJ sequences and regional indicator pairs stay together.
+fn extends_cluster(prev: char, c: char, regional_run: usize) -> bool {
+    c.general_category_group() == GeneralCategoryGroup::Mark
+        || is_emoji_modifier(c)
+        || unicode_properties::emoji::is_zwj(c)
+        || unicode_properties::emoji::is_zwj(prev)
+        || (unicode_properties::emoji::is_regional_indicator(c) && regional_run % 2 == 1)
+        || unicode_properties::emoji::is_tag_character(c)
+}
+
+/// Returns the byte length of the first `max_chars` grapheme clusters of `text`,
+/// or `None` if the text is already short enough.
+fn truncated_len(text: &str, max_chars: usize) -> Option<usize> {
+    let mut clusters = 0;
+    let mut prev: Option<char> = None;
+    let mut regional_run = 0;
+    for (idx, c) in text.char_indices() {
+        let continues = prev.is_some_and(|p| extends_cluster(p, c, regional_run));
+        if !continues {
+            if clusters == max_chars {
+                return Some(idx);
+            }
+            clusters += 1;
+        }
+        if unicode_properties::emoji::is_regional_indicator(c) {
+            regional_run += 1;
+        } else {
+            regional_run = 0;
+        }
+        prev = Some(c);
+    }
+    None
+}
+
+impl<T: TokenStream> TokenStream for TruncateTokenFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let text = self.text;
+        if let Some(len) = truncated_len(&self.tail.token().text, self.max_chars) {
+            let token = self.tail.token_mut();
+            // Shrink the offsets to end after the kept prefix, if the token is in the source.
+            let start = text
+                .get(token.offset_from..token.offset_to)
+                .and_then(|span| span.find(token.text.as_str()));
+            if let Some(start) = start {
+                token.offset_to = token.offset_from + start + len;
+            }
+            token.text.truncate(len);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, WhitespaceTokenizer};
+
+    use super::TruncateTokenFilter;
+
+    #[test]
+    fn test_truncate_token_filter() {
+        let tokens = token_stream_helper("internationalization is long", 8);
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "internat", 0, 8);
+        assert_token(&tokens[1], 1, "is", 21, 23);
+        assert_token(&tokens[2], 2, "long", 24, 28);
+    }
+
+    #[test]
+    fn test_truncate_token_filter_multi_byte() {
+        // "é" is two bytes, so truncating to 4 graphemes keeps 5 bytes
+        let tokens = token_stream_helper("résumés", 4);
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "résu", 0, 5);
+
+        // A decomposed "e" + combining acute accent counts as a single grapheme
+        let tokens = token_stream_helper("re\u{0301}sume\u{0301}s", 2);
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "re\u{0301}", 0, 4);
+
+        // Skin-toned emoji are kept whole
+        let tokens = token_stream_helper("👍🏽👍🏽👍🏽", 2);
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "👍🏽👍🏽", 0, 16);
+    }
+
+    #[test]
+    fn test_truncate_token_filter_stripped_quote() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec![]))
+            .filter(TruncateTokenFilter::new(2))
+            .build();
+        let text = "\"résumé\" ÉCOLE";
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+
+        // The offsets end after "ré" in the source, past the stripped quote
+        assert_token(&tokens[0], 0, "ré", 0, 4);
+        assert_eq!(&text[tokens[0].offset_from..tokens[0].offset_to], "\"ré");
+        assert_token(&tokens[1], 1, "ÉC", 11, 14);
+
+        // Lowercased tokens aren't found in the source, so their offsets are left whole
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(TruncateTokenFilter::new(2))
+            .build();
+        let mut token_stream = analyzer.token_stream("ÉCOLE");
+        assert!(token_stream.advance());
+        assert_token(token_stream.token(), 0, "éc", 0, 6);
+    }
+
+    #[test]
+    fn test_truncate_token_filter_short_token_unchanged() {
+        let tokens = token_stream_helper("tree", 4);
+        assert_eq!(tokens.len(), 1);
+        assert_token(&tokens[0], 0, "tree", 0, 4);
+    }
+
+    fn token_stream_helper(text: &str, max_chars: usize) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(TruncateTokenFilter::new(max_chars))
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
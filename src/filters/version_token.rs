@@ -0,0 +1,242 @@
+use crate::filters::split_parts::SplitPartsStream;
+use tantivy::tokenizer::Tokenizer;
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream};
+
+// 'VersionTokenFilter' keeps version numbers intact, e.g. "v1.2.3", "2.0.1", "v2.0" and
+// semantic versions with pre-release or build metadata ("1.0.0-rc.1", "1.2.3+build.5").
+// Like `NumericEntityFilter`, it looks at the token's original text in the source: if it is
+// a version once wrapping punctuation is trimmed, the token text is restored to it, undoing
+// anything earlier filters such as `OuterPunctuationFilter` stripped from it. The token's
+// casing is kept: if its letters were already lowercased, so is the restored version, so
+// "V2.0" and "v2.0" give the same term in a lowercasing analyzer.
+// Only a token that is part of the version is restored: if an earlier filter such as
+// `HyphenSplitFilter` split the version into parts sharing its offsets, or the token text
+// isn't found in the version, it is left alone, so the version is never emitted twice.
+// Recognized versions are "vN.N" with an optional third part (the "v" is case-insensitive),
+// or a bare "N.N.N"; a bare "N.N" is a decimal, not a version. Other dotted tokens
+// ("example.com", "e.g.") are passed through unchanged.
+
+#[derive(Clone)]
+pub struct VersionTokenFilter;
+
+impl TokenFilter for VersionTokenFilter {
+    type Tokenizer<T: Tokenizer> = VersionTokenFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> VersionTokenFilterWrapper<T> {
+        VersionTokenFilterWrapper { inner: tokenizer }
+    }
+}
+
+#[derive(Clone)]
+pub struct VersionTokenFilterWrapper<T> {
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for VersionTokenFilterWrapper<T> {
+    type TokenStream<'a> = VersionTokenFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        VersionTokenFilterTokenStream {
+            text,
+            tail: SplitPartsStream::new(self.inner.token_stream(text)),
+        }
+    }
+}
+
+pub struct VersionTokenFilterTokenStream<'a, T> {
+    // The original text, used to recover characters stripped by earlier filters.
+    text: &'a str,
+    tail: SplitPartsStream<T>,
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty()
+        && text.split('.').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+// Checks if text is "vN.N", "vN.N.N" or "N.N.N", with optional semver pre-release and build
+// suffixes.
+fn is_version(text: &str) -> bool {
+    let (core, build) = match text.split_once('+') {
+        Some((core, build)) => (core, Some(build)),
+        None => (text, None),
+    };
+    if build.is_some_and(|build| !is_identifier(build)) {
+        return false;
+    }
+    let (core, pre_release) = match core.split_once('-') {
+        Some((core, pre_release)) => (core, Some(pre_release)),
+        None => (core, None),
+    };
+    if pre_release.is_some_and(|pre_release| !is_identifier(pre_release)) {
+        return false;
+    }
+
+    let (prefixed, numbers) = match core.strip_prefix(['v', 'V']) {
+        Some(numbers) => (true, numbers),
+        None => (false, core),
+    };
+    let parts: Vec<&str> = numbers.split('.').collect();
+    let min_parts = if prefixed { 2 } else { 3 };
+    (min_parts..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+// Checks if text has letters and all of them are lowercase.
+fn is_lowercased(text: &str) -> bool {
+    text.chars().any(char::is_alphabetic) && !text.chars().any(char::is_uppercase)
+}
+
+impl<T: TokenStream> TokenStream for VersionTokenFilterTokenStream<'_, T> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+
+        let token = self.tail.token();
+        let span = self
+            .text
+            .get(token.offset_from..token.offset_to)
+            .unwrap_or("");
+        let candidate = span
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .trim_end_matches(|c: char| !c.is_alphanumeric());
+        if candidate != token.text
+            && !self.tail.is_split_part()
+            && is_version(candidate)
+            && candidate
+                .to_lowercase()
+                .contains(&token.text.to_lowercase())
+        {
+            let lowercased = is_lowercased(&token.text);
+            self.tail.token_mut().text = if lowercased {
+                candidate.to_lowercase()
+            } else {
+                candidate.to_string()
+            };
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filters::outer_punctuation::tests::assert_token;
+    use crate::filters::{
+        AcronymMode, DotHandlingFilter, HyphenSplitFilter, OuterPunctuationFilter,
+    };
+    use tantivy::tokenizer::{LowerCaser, TextAnalyzer, Token, TokenStream, WhitespaceTokenizer};
+
+    use super::{is_lowercased, is_version, VersionTokenFilter};
+
+    #[test]
+    fn test_version_token_filter() {
+        let tokens = token_stream_helper("(v1.2.3), 2.0.1. example.com.");
+        assert_eq!(tokens.len(), 3);
+        assert_token(&tokens[0], 0, "v1.2.3", 0, 9);
+        assert_token(&tokens[1], 1, "2.0.1", 10, 16);
+        // Not a version, so default punctuation stripping applies
+        assert_token(&tokens[2], 2, "example.com", 17, 29);
+    }
+
+    #[test]
+    fn test_version_token_filter_keeps_case_and_suffixes() {
+        // Versions stay lowercased, so both spellings give one term; acronym handling
+        // doesn't apply to them
+        let tokens = token_stream_helper("V2.0 v2.0 1.0.0-RC.1 U.S.");
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["v2.0", "v2.0", "1.0.0-rc.1", "us"]);
+
+        // Without lowercasing, the source casing is kept
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(VersionTokenFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream("(V2.0) 1.0.0-RC.1.");
+        let mut texts = vec![];
+        while token_stream.advance() {
+            texts.push(token_stream.token().text.clone());
+        }
+        assert_eq!(texts, vec!["V2.0", "1.0.0-RC.1"]);
+    }
+
+    #[test]
+    fn test_version_token_filter_after_splitting() {
+        // Split parts share the version's offsets and are not restored to it
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(HyphenSplitFilter::new())
+            .filter(VersionTokenFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream("(1.0.0-rc.1) v2.0.");
+        let mut tokens = vec![];
+        while token_stream.advance() {
+            let token = token_stream.token();
+            tokens.push((token.text.clone(), token.position));
+        }
+        assert_eq!(
+            tokens,
+            vec![
+                ("1.0.0".to_string(), 0),
+                ("rc.1".to_string(), 0),
+                ("v2.0".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_lowercased() {
+        assert!(is_lowercased("v2.0"));
+        assert!(!is_lowercased("V2.0"));
+        // Without letters there is no casing to keep, so the source casing is used
+        assert!(!is_lowercased("2.0.1"));
+        assert!(!is_lowercased(""));
+    }
+
+    #[test]
+    fn test_is_version() {
+        for version in ["v1.2", "V1.2.3", "2.0.1", "1.0.0-rc.1", "1.2.3+build.5"] {
+            assert!(is_version(version), "{}", version);
+        }
+        for other in [
+            "2.0",
+            "v1",
+            "1.2.3.4",
+            "v1..2",
+            "1.2.3-",
+            "vx.1",
+            "example.com",
+        ] {
+            assert!(!is_version(other), "{}", other);
+        }
+    }
+
+    fn token_stream_helper(text: &str) -> Vec<Token> {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(DotHandlingFilter::new(AcronymMode::Merge))
+            .filter(VersionTokenFilter)
+            .build();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = vec![];
+        let mut add_token = |token: &Token| {
+            tokens.push(token.clone());
+        };
+        token_stream.process(&mut add_token);
+        tokens
+    }
+}
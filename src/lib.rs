@@ -9,7 +9,36 @@
 //!   with configurable exceptions for specific characters (e.g., '#', '@')
 //! - **PossessiveContractionFilter**: Removes possessive contractions (apostrophe-s variants)
 //!   using Unicode-aware matching
-//! - **Pre-built analyzers**: Ready-to-use analyzer configurations combining filters
+//! - **TruncateTokenFilter**: Truncates long tokens to a maximum number of grapheme clusters
+//! - **OcrNormalizationFilter**: Repairs common OCR confusions such as "0rder" -> "order"
+//! - **HashtagSegmentFilter**: Splits hashtags into dictionary words (e.g. "#blacklivesmatter")
+//! - **FoldLowerFilter**: Removes diacritics and lowercases in a single pass
+//! - **DotHandlingFilter**: Merges or splits dotted acronyms while keeping domains whole
+//! - **PiiPlaceholderFilter**: Replaces phone numbers and currency amounts with placeholders
+//! - **EmphasisPunctuationFilter**: Turns "!!!" and "???" into sentiment marker tokens
+//! - **SharedBlocklistFilter**: Drops tokens found in a blocklist that can be updated at runtime
+//! - **HyphenSplitFilter**: Splits hyphenated tokens, optionally marking negations ("no-good" -> "NOT_good")
+//! - **RangeDashFilter**: Splits numeric ranges like "2020–2024" or normalizes their dash
+//! - **EntropyFilter**: Drops random-looking tokens (IDs, hashes) by character entropy
+//! - **SimplePluralFilter**: Lightweight plural normalization ("cats" -> "cat", "boxes" -> "box")
+//! - **NumericEntityFilter**: Keeps numbers, decimals, percentages and fractions intact ("12.5%")
+//! - **CliticSplitFilter**: Splits contractions into base and marked clitic ("we'll" -> "we", "'ll")
+//! - **NumberUnitMergeFilter**: Merges numbers with a following unit ("5 km" -> "5km")
+//! - **VersionTokenFilter**: Keeps version numbers intact ("v1.2.3", "2.0.1")
+//! - **ArabicNormalizationFilter**: Folds Arabic and Persian letter variants ("أ" -> "ا", "ی" -> "ي")
+//! - **SmartLowerCaser**: Lowercases tokens but keeps all-caps acronyms ("NASA")
+//! - **CharNgramFilter**: Emits overlapping character n-grams for fuzzy matching ("hello" -> "hel", "ell", "llo")
+//! - **PathNoiseFilter**: Drops or replaces path-like tokens and bare extensions ("/usr/bin", ".txt")
+//! - **StopWordMarkFilter**: Marks stopwords as stopped (position `usize::MAX`) instead of removing them
+//! - **NumberWordFilter**: Converts small numbers to English words or back ("3" <-> "three")
+//! - **EmojiNormalizationFilter**: Strips skin tones and variation selectors from emoji ("👍🏽" -> "👍")
+//! - **PositionGapFilter**: Leaves a fixed position gap where tokens were removed
+//! - **ExceptionBodyLowerFilter**: Lowercases hashtag and mention bodies while keeping the leading marker ("#NewsUpdate" -> "#newsupdate")
+//! - **RepeatedApostropheFilter**: Collapses doubled or zero-width-split apostrophes ("don''t" -> "don't")
+//! - **IdentifierSplitFilter**: Splits code identifiers on underscores and camelCase ("getUserID" -> "get", "User", "ID")
+//! - **BidiControlFilter**: Drops tokens made only of bidi/format controls, optionally stripping embedded bidi controls
+//! - **Pre-built analyzers**: Ready-to-use analyzer configurations combining filters,
+//!   plus `KapicheAnalyzerConfig` for building custom combinations
 //! - **Token counting utility**: Fast streaming token counter without memory allocation
 //!
 //! # Examples
@@ -31,7 +60,17 @@ pub mod utils;
 
 // Re-export commonly used items for convenience
 pub use analyzers::{
-    kapiche_analyzer, kapiche_analyzer_lower, kapiche_analyzer_lower_with_stopwords,
+    code_search_analyzer, from_spec, kapiche_analyzer, kapiche_analyzer_lower,
+    kapiche_analyzer_lower_with_stopwords, KapicheAnalyzerConfig,
+};
+pub use filters::{
+    ArabicNormalizationFilter, BidiControlFilter, CharNgramFilter, CliticSplitFilter,
+    DotHandlingFilter, EmojiNormalizationFilter, EmphasisPunctuationFilter, EntropyFilter,
+    ExceptionBodyLowerFilter, FoldLowerFilter, HashtagSegmentFilter, HyphenSplitFilter,
+    IdentifierSplitFilter, NumberUnitMergeFilter, NumberWordFilter, NumericEntityFilter,
+    OcrNormalizationFilter, OuterPunctuationFilter, PathNoiseFilter, PiiPlaceholderFilter,
+    PositionGapFilter, PossessiveContractionFilter, RangeDashFilter, RepeatedApostropheFilter,
+    SharedBlocklistFilter, SimplePluralFilter, SmartLowerCaser, StopWordMarkFilter,
+    TruncateTokenFilter, VersionTokenFilter,
 };
-pub use filters::{OuterPunctuationFilter, PossessiveContractionFilter};
 pub use utils::count_tokens;
@@ -1,4 +1,32 @@
-use tantivy::tokenizer::TextAnalyzer;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use tantivy::tokenizer::{TextAnalyzer, WhitespaceTokenizer};
+
+use crate::analyzers::{cached, KapicheAnalyzerConfig};
+use crate::filters::is_stopword_en;
+
+/// Clean up text pasted from other systems before tokenizing it.
+///
+/// A leading byte-order mark (U+FEFF) would otherwise end up in the first token, and
+/// Windows (CRLF) or old Mac (CR) line endings leave stray "\r" characters in tokens
+/// for tokenizers that only split on "\n". Run this on raw input before passing it to
+/// an analyzer.
+///
+/// # Arguments
+/// * `text` - The raw text
+///
+/// # Returns
+/// The text without a leading BOM and with all line endings normalized to "\n",
+/// borrowed from the input when no line endings needed to change
+pub fn preclean(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+    if !text.contains('\r') {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+}
 
 /// Count non-stopped tokens in text without allocating a collection.
 ///
@@ -35,10 +63,920 @@ pub fn count_tokens(analyzer: &mut TextAnalyzer, text: &str) -> usize {
     count
 }
 
+/// Estimate the token count of text without running an analyzer.
+///
+/// Counts whitespace-separated runs, which is much cheaper than `count_tokens` and
+/// useful for pre-screening text before deciding whether to run the full pipeline.
+/// It is an upper bound for the Kapiche analyzers, as filters can only remove tokens
+/// (stopwords, punctuation-only tokens) after whitespace splitting. Pipelines that
+/// split tokens further, such as `HyphenSplitFilter` or a `SimpleTokenizer`, can
+/// produce more tokens than the estimate.
+///
+/// # Arguments
+/// * `text` - The text to estimate
+///
+/// # Returns
+/// The number of whitespace-separated runs in the text
+pub fn estimate_token_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Count non-stopped tokens that have content beyond preserved exception characters.
+///
+/// Like `count_tokens`, but skips tokens made up only of the given exception
+/// characters (e.g. the '#' and '@' that `OuterPunctuationFilter` preserves), as well
+/// as tokens left empty by punctuation stripping. "#### @@@ hello" counts as 1.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `exceptions` - Characters that don't count as content on their own
+///
+/// # Returns
+/// The count of tokens with at least one non-exception character
+pub fn count_content_tokens(analyzer: &mut TextAnalyzer, text: &str, exceptions: &[char]) -> usize {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut count = 0;
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && token.text.chars().any(|c| !exceptions.contains(&c)) {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Count non-stopped tokens that are not stopped by a custom predicate.
+///
+/// Like `count_tokens`, but tokens for which `is_stop` returns true are also
+/// skipped, which allows fully dynamic stopword logic without rebuilding the
+/// analyzer. The predicate sees the token text after all filters have run.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `is_stop` - Returns true for tokens that should not be counted
+///
+/// # Returns
+/// The count of tokens kept by both the analyzer and the predicate
+pub fn count_tokens_pred_stop(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    is_stop: impl Fn(&str) -> bool,
+) -> usize {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut count = 0;
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !is_stop(&token.text) {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Compute the positional span a document occupies.
+///
+/// Removed stopwords leave gaps in token positions, so the span can be larger than
+/// the number of kept tokens: "The cat sat on the mat" keeps 3 tokens with English
+/// stopwords removed but spans positions 1 to 5. Use both figures for phrase-slop tuning.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// The highest minus the lowest non-stopped position plus one, or 0 if no tokens are kept
+pub fn position_span(analyzer: &mut TextAnalyzer, text: &str) -> usize {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut bounds: Option<(usize, usize)> = None;
+
+    while token_stream.advance() {
+        let position = token_stream.token().position;
+        if position == usize::MAX {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((min, max)) => (min.min(position), max.max(position)),
+            None => (position, position),
+        });
+    }
+
+    bounds.map_or(0, |(min, max)| max - min + 1)
+}
+
+/// Collect non-stopped tokens paired with their positions.
+///
+/// Tokens are returned in exact source order, including duplicates, so the output
+/// is stable for a given analyzer and text. Positions come from the analyzer, so
+/// gaps left by removed stopwords are preserved, which lets callers sort or group
+/// tokens deterministically. Tokens left empty by punctuation stripping are skipped,
+/// as in every collecting utility.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// A vector of `(token_text, position)` pairs in source order
+pub fn tokens_with_positions(analyzer: &mut TextAnalyzer, text: &str) -> Vec<(String, usize)> {
+    tokens_with_positions_with_capacity(analyzer, text, 0)
+}
+
+/// Like `tokens_with_positions`, but pre-sizes the output for `capacity` tokens.
+///
+/// The output is identical to `tokens_with_positions`; a good hint avoids
+/// reallocating the vector while tokens are collected. Use
+/// `KapicheAnalyzerConfig::tokens_per_doc_hint` to keep the hint with the analyzer config.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `capacity` - The expected number of tokens
+///
+/// # Returns
+/// A vector of `(token_text, position)` pairs in source order
+pub fn tokens_with_positions_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    capacity: usize,
+) -> Vec<(String, usize)> {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut tokens = Vec::with_capacity(capacity);
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !token.text.is_empty() {
+            tokens.push((token.text.clone(), token.position));
+        }
+    }
+
+    tokens
+}
+
+/// Collect every token paired with whether the analyzer marked it stopped.
+///
+/// Unlike the other utilities, stopped tokens (position == usize::MAX) are included,
+/// which lets stopword decisions be made downstream. This needs an analyzer that marks
+/// stopwords rather than dropping them, e.g. one built with `StopWordMarkFilter` in place
+/// of `StopWordFilter`; with a removing analyzer every token is paired with `false`.
+/// Tokens left empty by punctuation stripping are skipped.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// A vector of `(token_text, stopped)` pairs in source order
+///
+/// # Example
+/// ```
+/// use tantivy::tokenizer::{LowerCaser, TextAnalyzer, WhitespaceTokenizer};
+/// use tantivy_tokenizers::filters::{get_stopwords_filter_en, StopWordMarkFilter};
+/// use tantivy_tokenizers::utils::tokens_with_stopped;
+///
+/// let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+///     .filter(LowerCaser)
+///     .filter(StopWordMarkFilter::new(get_stopwords_filter_en()))
+///     .build();
+/// let tokens = tokens_with_stopped(&mut analyzer, "The cat");
+/// assert_eq!(tokens, vec![("the".to_string(), true), ("cat".to_string(), false)]);
+/// ```
+pub fn tokens_with_stopped(analyzer: &mut TextAnalyzer, text: &str) -> Vec<(String, bool)> {
+    tokens_with_stopped_with_capacity(analyzer, text, 0)
+}
+
+/// Like `tokens_with_stopped`, but pre-sizes the output for `capacity` tokens.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `capacity` - The expected number of tokens
+///
+/// # Returns
+/// A vector of `(token_text, stopped)` pairs in source order
+pub fn tokens_with_stopped_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    capacity: usize,
+) -> Vec<(String, bool)> {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut tokens = Vec::with_capacity(capacity);
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if !token.text.is_empty() {
+            tokens.push((token.text.clone(), token.position == usize::MAX));
+        }
+    }
+
+    tokens
+}
+
+/// Collect the distinct non-stopped token texts of a document.
+///
+/// Tokens left empty by punctuation stripping are skipped, as in `clean_text`.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// The set of unique token texts (excluding stopped and empty tokens)
+pub fn unique_tokens(analyzer: &mut TextAnalyzer, text: &str) -> HashSet<String> {
+    unique_tokens_with_capacity(analyzer, text, 0)
+}
+
+/// Like `unique_tokens`, but pre-sizes the output set for `capacity` tokens.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `capacity` - The expected number of tokens
+///
+/// # Returns
+/// The set of distinct token texts
+pub fn unique_tokens_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    capacity: usize,
+) -> HashSet<String> {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut tokens = HashSet::with_capacity(capacity);
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !token.text.is_empty() && !tokens.contains(&token.text) {
+            tokens.insert(token.text.clone());
+        }
+    }
+
+    tokens
+}
+
+/// Collect the distinct non-stopped token texts of a document in sorted order.
+///
+/// A deterministic alternative to `unique_tokens`, e.g. for building query expansions.
+/// Tokens are sorted lexicographically by their UTF-8 bytes.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// The unique token texts (excluding stopped tokens), sorted
+pub fn sorted_unique_tokens(analyzer: &mut TextAnalyzer, text: &str) -> Vec<String> {
+    sorted_unique_tokens_with_capacity(analyzer, text, 0)
+}
+
+/// Like `sorted_unique_tokens`, but pre-sizes the set used to deduplicate for `capacity`
+/// tokens.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `capacity` - The expected number of tokens
+///
+/// # Returns
+/// The unique token texts (excluding stopped tokens), sorted
+pub fn sorted_unique_tokens_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    capacity: usize,
+) -> Vec<String> {
+    let mut tokens: Vec<String> = unique_tokens_with_capacity(analyzer, text, capacity)
+        .into_iter()
+        .collect();
+    tokens.sort_unstable();
+    tokens
+}
+
+/// Compute the document frequency of every term in a corpus.
+///
+/// Each term is counted at most once per document, so the result is the number
+/// of documents the term appears in, as used for IDF in TF-IDF scoring.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `docs` - The documents in the corpus
+///
+/// # Returns
+/// A map from term to the number of documents containing it
+pub fn document_frequencies(analyzer: &mut TextAnalyzer, docs: &[&str]) -> HashMap<String, usize> {
+    document_frequencies_with_capacity(analyzer, docs, 0)
+}
+
+/// Like `document_frequencies`, but pre-sizes the per-document set of terms for
+/// `capacity` tokens and the result for `capacity` distinct terms.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `docs` - The documents in the corpus
+/// * `capacity` - The expected number of tokens per document
+///
+/// # Returns
+/// A map from term to the number of documents containing it
+pub fn document_frequencies_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    docs: &[&str],
+    capacity: usize,
+) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::with_capacity(capacity);
+
+    for doc in docs {
+        for term in unique_tokens_with_capacity(analyzer, doc, capacity) {
+            *frequencies.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    frequencies
+}
+
+/// Rejoin the analyzed tokens of a text into a readable string.
+///
+/// Non-stopped tokens are joined with `sep` in source order, giving a "cleaned"
+/// preview of what the analyzer indexes. Tokens left empty by punctuation
+/// stripping are skipped so they don't produce doubled separators.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `sep` - The separator placed between tokens
+///
+/// # Returns
+/// The token texts joined with `sep`
+pub fn clean_text(analyzer: &mut TextAnalyzer, text: &str, sep: &str) -> String {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut cleaned = String::with_capacity(text.len());
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !token.text.is_empty() {
+            if !cleaned.is_empty() {
+                cleaned.push_str(sep);
+            }
+            cleaned.push_str(&token.text);
+        }
+    }
+
+    cleaned
+}
+
+/// Collect the first `n` tokens of a text, for short previews.
+///
+/// Tokenization stops as soon as `n` tokens have been collected, so this is cheap
+/// on long documents. Stopped tokens and tokens left empty by punctuation
+/// stripping are skipped and don't count towards `n`.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `n` - The maximum number of tokens to return
+///
+/// # Returns
+/// Up to `n` token texts in source order
+pub fn leading_tokens(analyzer: &mut TextAnalyzer, text: &str, n: usize) -> Vec<String> {
+    let mut tokens = Vec::new();
+    if n == 0 {
+        return tokens;
+    }
+    let mut token_stream = analyzer.token_stream(text);
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !token.text.is_empty() {
+            tokens.push(token.text.clone());
+            if tokens.len() == n {
+                break;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Collect the tokens of a text that are not in a vocabulary.
+///
+/// Tokens are returned in source order, including duplicates, so the result can
+/// be used directly for out-of-vocabulary rates. Stopped tokens and tokens left
+/// empty by punctuation stripping are not considered words and are skipped.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `vocab` - The known words, in the form the analyzer produces them
+///
+/// # Returns
+/// The token texts not found in `vocab`
+pub fn oov_tokens(analyzer: &mut TextAnalyzer, text: &str, vocab: &HashSet<String>) -> Vec<String> {
+    oov_tokens_with_capacity(analyzer, text, vocab, 0)
+}
+
+/// Like `oov_tokens`, but pre-sizes the output for `capacity` tokens.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `vocab` - The known words, in the form the analyzer produces them
+/// * `capacity` - The expected number of tokens
+///
+/// # Returns
+/// The token texts not found in `vocab`
+pub fn oov_tokens_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    vocab: &HashSet<String>,
+    capacity: usize,
+) -> Vec<String> {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut oov = Vec::with_capacity(capacity);
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !token.text.is_empty() && !vocab.contains(&token.text) {
+            oov.push(token.text.clone());
+        }
+    }
+
+    oov
+}
+
+/// Tokenize several fields in turn, separating their positions by a gap.
+///
+/// Each field's positions continue from the end of the previous field plus
+/// `position_gap`, so phrase queries cannot match across field boundaries
+/// when the fields are indexed together. Tokens are returned in source order.
+/// Tokens left empty by punctuation stripping are skipped but still count towards
+/// the field's positions.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `texts` - The field texts, in order
+/// * `position_gap` - Extra positions inserted between consecutive fields
+///
+/// # Returns
+/// A vector of `(token_text, position)` pairs for the non-stopped tokens of all fields
+pub fn tokenize_multi_with_gap(
+    analyzer: &mut TextAnalyzer,
+    texts: &[&str],
+    position_gap: usize,
+) -> Vec<(String, usize)> {
+    let mut tokens = Vec::new();
+    let mut base = 0;
+
+    for (i, text) in texts.iter().enumerate() {
+        if i > 0 {
+            base += position_gap;
+        }
+        let mut token_stream = analyzer.token_stream(text);
+        let mut span = 0;
+        while token_stream.advance() {
+            let token = token_stream.token();
+            if token.position != usize::MAX {
+                if !token.text.is_empty() {
+                    tokens.push((token.text.clone(), base + token.position));
+                }
+                span = span.max(token.position + 1);
+            }
+        }
+        base += span;
+    }
+
+    tokens
+}
+
+/// Character-length bucket of a token, for weighting tokens by length downstream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LengthClass {
+    /// Up to 3 characters ("a", "the").
+    Short,
+    /// 4 to 7 characters.
+    Medium,
+    /// 8 to 12 characters.
+    Long,
+    /// 13 characters or more.
+    VeryLong,
+}
+
+impl LengthClass {
+    /// Returns the class of a token with `chars` characters.
+    pub fn from_char_len(chars: usize) -> LengthClass {
+        match chars {
+            0..=3 => LengthClass::Short,
+            4..=7 => LengthClass::Medium,
+            8..=12 => LengthClass::Long,
+            _ => LengthClass::VeryLong,
+        }
+    }
+}
+
+/// Collect non-stopped tokens paired with their length class.
+///
+/// Lengths are counted in characters, not bytes, so accented and non-Latin
+/// tokens are classed like their ASCII counterparts. Tokens left empty by punctuation
+/// stripping are skipped.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// A vector of `(token_text, length_class)` pairs in source order
+pub fn tokens_with_length_class(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+) -> Vec<(String, LengthClass)> {
+    tokens_with_length_class_with_capacity(analyzer, text, 0)
+}
+
+/// Like `tokens_with_length_class`, but pre-sizes the output for `capacity` tokens.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+/// * `capacity` - The expected number of tokens
+///
+/// # Returns
+/// A vector of `(token_text, length_class)` pairs in source order
+pub fn tokens_with_length_class_with_capacity(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    capacity: usize,
+) -> Vec<(String, LengthClass)> {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut tokens = Vec::with_capacity(capacity);
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX && !token.text.is_empty() {
+            let class = LengthClass::from_char_len(token.text.chars().count());
+            tokens.push((token.text.clone(), class));
+        }
+    }
+
+    tokens
+}
+
+/// Tokenize several named fields, tagging each token with its source field.
+///
+/// Fields are analyzed separately and in order, so tokens never merge across
+/// field boundaries, and every token is returned with the name of its field.
+/// Tokens left empty by punctuation stripping are skipped.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `fields` - `(field_name, text)` pairs, in order
+///
+/// # Returns
+/// A vector of `(field_name, token_text)` pairs for the non-stopped tokens of all fields
+pub fn tokenize_tagged(
+    analyzer: &mut TextAnalyzer,
+    fields: &[(&str, &str)],
+) -> Vec<(String, String)> {
+    let mut tokens = Vec::new();
+
+    for (field, text) in fields {
+        let mut token_stream = analyzer.token_stream(text);
+        while token_stream.advance() {
+            let token = token_stream.token();
+            if token.position != usize::MAX && !token.text.is_empty() {
+                tokens.push((field.to_string(), token.text.clone()));
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Tokenize text for both phrase matching and relevance scoring in a single pass.
+///
+/// The first vector matches the output of `kapiche_analyzer_lower()`, which keeps
+/// stopwords for phrase queries. The second matches
+/// `kapiche_analyzer_lower_with_stopwords()`, keeping only content words for scoring.
+/// The analyzer is the cached `KapicheAnalyzerConfig` pipeline with stopwords marked
+/// rather than removed (see `dual_tokenize_with`).
+///
+/// # Arguments
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// A pair of `(all_tokens, content_tokens)` in source order
+pub fn dual_tokenize(text: &str) -> (Vec<String>, Vec<String>) {
+    let config = KapicheAnalyzerConfig::new()
+        .lowercase(true)
+        .stopwords(true)
+        .mark_stopwords(true);
+    dual_tokenize_with(&mut cached(config), text)
+}
+
+/// Like `dual_tokenize`, but with an analyzer that marks stopwords instead of removing
+/// them, e.g. one built from a `KapicheAnalyzerConfig` with `mark_stopwords(true)`.
+///
+/// Every token goes into the first vector and tokens that aren't stopped also go into the
+/// second. Tokens left empty by punctuation stripping are skipped.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// A pair of `(all_tokens, content_tokens)` in source order
+pub fn dual_tokenize_with(analyzer: &mut TextAnalyzer, text: &str) -> (Vec<String>, Vec<String>) {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut all = Vec::new();
+    let mut content = Vec::new();
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.text.is_empty() {
+            continue;
+        }
+        if token.position != usize::MAX {
+            content.push(token.text.clone());
+        }
+        all.push(token.text.clone());
+    }
+
+    (all, content)
+}
+
+/// Differences between the outputs of two analyzers on the same text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnalyzerDiff {
+    /// Tokens produced only by the first analyzer, in source order.
+    pub only_in_a: Vec<String>,
+    /// Tokens produced only by the second analyzer, in source order.
+    pub only_in_b: Vec<String>,
+    /// Tokens produced by both analyzers at different positions, as
+    /// `(token_text, position_in_a, position_in_b)`.
+    pub moved: Vec<(String, usize, usize)>,
+}
+
+impl AnalyzerDiff {
+    /// Returns true if both analyzers produced the same tokens at the same positions.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.moved.is_empty()
+    }
+}
+
+/// Compare the tokens two analyzers produce for a text.
+///
+/// Repeated tokens are matched occurrence by occurrence, so a token produced twice
+/// by one analyzer and once by the other is reported once. Matched occurrences at
+/// different positions are reported as moved. This is meant for checking that an
+/// analyzer change is safe before reindexing.
+///
+/// # Arguments
+/// * `a` - The first analyzer, e.g. the current one
+/// * `b` - The second analyzer, e.g. the proposed one
+/// * `text` - The text to analyze
+///
+/// # Returns
+/// An `AnalyzerDiff` that is empty if both outputs are identical
+pub fn diff_analyzers(a: &mut TextAnalyzer, b: &mut TextAnalyzer, text: &str) -> AnalyzerDiff {
+    let tokens_a = tokens_with_positions(a, text);
+    let tokens_b = tokens_with_positions(b, text);
+
+    // Indices into `tokens_b` of each token text, consumed in order as occurrences are matched.
+    let mut occurrences_b: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (i, (text, _)) in tokens_b.iter().enumerate() {
+        occurrences_b.entry(text).or_default().push_back(i);
+    }
+    let mut matched_b = vec![false; tokens_b.len()];
+
+    let mut diff = AnalyzerDiff::default();
+    for (text, position_a) in &tokens_a {
+        let Some(i) = occurrences_b
+            .get_mut(text.as_str())
+            .and_then(VecDeque::pop_front)
+        else {
+            diff.only_in_a.push(text.clone());
+            continue;
+        };
+        matched_b[i] = true;
+        let position_b = tokens_b[i].1;
+        if position_b != *position_a {
+            diff.moved.push((text.clone(), *position_a, position_b));
+        }
+    }
+    diff.only_in_b = tokens_b
+        .iter()
+        .zip(matched_b)
+        .filter(|(_, matched)| !matched)
+        .map(|((text, _), _)| text.clone())
+        .collect();
+
+    diff
+}
+
+/// Summary statistics of a corpus, computed by `corpus_stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CorpusStats {
+    /// Number of documents analyzed.
+    pub documents: usize,
+    /// Total number of tokens across all documents.
+    pub total_tokens: usize,
+    /// Number of distinct tokens across all documents.
+    pub unique_tokens: usize,
+    /// Mean token length in characters, 0 for an empty corpus.
+    pub mean_token_length: f64,
+    /// Fraction of tokens that are Kapiche English stopwords, 0 for an empty corpus.
+    pub stopword_ratio: f64,
+}
+
+/// Profile a corpus in a single pass over every document.
+///
+/// Stopped tokens and tokens left empty by punctuation stripping are not counted.
+/// Stopwords are identified with `is_stopword_en` on the analyzed tokens, so use an
+/// analyzer that lowercases and keeps stopwords (e.g. `kapiche_analyzer_lower()`) for
+/// a meaningful stopword ratio.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to use for tokenization
+/// * `docs` - The documents in the corpus
+///
+/// # Returns
+/// A `CorpusStats` summarizing the corpus
+pub fn corpus_stats(analyzer: &mut TextAnalyzer, docs: &[&str]) -> CorpusStats {
+    let mut vocabulary = HashSet::new();
+    let mut total_tokens = 0;
+    let mut total_chars = 0;
+    let mut stopwords = 0;
+
+    for doc in docs {
+        let mut token_stream = analyzer.token_stream(doc);
+        while token_stream.advance() {
+            let token = token_stream.token();
+            if token.position == usize::MAX || token.text.is_empty() {
+                continue;
+            }
+            total_tokens += 1;
+            total_chars += token.text.chars().count();
+            if is_stopword_en(&token.text) {
+                stopwords += 1;
+            }
+            if !vocabulary.contains(&token.text) {
+                vocabulary.insert(token.text.clone());
+            }
+        }
+    }
+
+    let ratio = |count: usize| {
+        if total_tokens == 0 {
+            0.0
+        } else {
+            count as f64 / total_tokens as f64
+        }
+    };
+    CorpusStats {
+        documents: docs.len(),
+        total_tokens,
+        unique_tokens: vocabulary.len(),
+        mean_token_length: ratio(total_chars),
+        stopword_ratio: ratio(stopwords),
+    }
+}
+
+/// Aggregate counts of tokens removed by each stage of a Kapiche analyzer pipeline.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FilterStats {
+    /// Number of documents analyzed.
+    pub documents: usize,
+    /// Tokens produced by the tokenizer before any filtering.
+    pub input_tokens: usize,
+    /// Tokens left empty after leading/trailing punctuation was stripped (e.g. "...").
+    pub punctuation_emptied: usize,
+    /// Tokens removed by the stopword filter.
+    pub stopwords_removed: usize,
+    /// Non-empty tokens remaining at the end of the pipeline.
+    pub output_tokens: usize,
+}
+
+// Returns (non-stopped tokens, non-stopped tokens with empty text).
+fn count_tokens_and_empty(analyzer: &mut TextAnalyzer, text: &str) -> (usize, usize) {
+    let mut token_stream = analyzer.token_stream(text);
+    let mut count = 0;
+    let mut empty = 0;
+
+    while token_stream.advance() {
+        let token = token_stream.token();
+        if token.position != usize::MAX {
+            count += 1;
+            if token.text.is_empty() {
+                empty += 1;
+            }
+        }
+    }
+
+    (count, empty)
+}
+
+/// Count how many tokens each filter of a Kapiche analyzer removes across a corpus.
+///
+/// Runs incrementally larger pipelines over every document and diffs the counts,
+/// which shows which filters are actually doing work for a given corpus.
+///
+/// # Arguments
+/// * `config` - The analyzer configuration to profile
+/// * `docs` - The documents to analyze
+///
+/// # Returns
+/// A `FilterStats` with the per-filter removal counts summed over all documents
+pub fn filter_removal_stats(config: &KapicheAnalyzerConfig, docs: &[&str]) -> FilterStats {
+    let mut tokenizer = TextAnalyzer::from(WhitespaceTokenizer::default());
+    let mut without_stopwords = config.clone().stopwords(false).build();
+    let mut full = config.build();
+
+    let mut stats = FilterStats {
+        documents: docs.len(),
+        ..FilterStats::default()
+    };
+
+    for doc in docs {
+        stats.input_tokens += count_tokens(&mut tokenizer, doc);
+
+        let (count, empty) = count_tokens_and_empty(&mut without_stopwords, doc);
+        stats.punctuation_emptied += empty;
+
+        let (full_count, full_empty) = count_tokens_and_empty(&mut full, doc);
+        stats.stopwords_removed += count - full_count;
+        stats.output_tokens += full_count - full_empty;
+    }
+
+    stats
+}
+
+/// Throughput of an analyzer measured by `measure_throughput`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThroughputStats {
+    /// Number of timed iterations.
+    pub iterations: usize,
+    /// Non-stopped tokens produced by a single pass over the text.
+    pub tokens_per_iteration: usize,
+    /// Total time spent in the timed iterations.
+    pub elapsed: Duration,
+    /// Average non-stopped tokens produced per second.
+    pub tokens_per_sec: f64,
+    /// Average bytes of input text analyzed per second.
+    pub bytes_per_sec: f64,
+}
+
+/// Measure how quickly an analyzer processes a piece of text.
+///
+/// The text is analyzed once to warm up before `iterations` timed passes, and the
+/// rates are averaged over the timed passes. This gives a standard way to compare
+/// analyzer configurations, but it is a micro-benchmark: run it on representative
+/// text and in release builds before drawing conclusions.
+///
+/// # Arguments
+/// * `analyzer` - The TextAnalyzer to measure
+/// * `text` - The text to analyze on every iteration
+/// * `iterations` - The number of timed passes
+///
+/// # Returns
+/// A `ThroughputStats` with token and byte rates (zero if `iterations` is 0)
+pub fn measure_throughput(
+    analyzer: &mut TextAnalyzer,
+    text: &str,
+    iterations: usize,
+) -> ThroughputStats {
+    let tokens_per_iteration = count_tokens(analyzer, text);
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(count_tokens(analyzer, std::hint::black_box(text)));
+    }
+    let elapsed = start.elapsed();
+
+    let (tokens_per_sec, bytes_per_sec) = if iterations == 0 {
+        (0.0, 0.0)
+    } else {
+        // Guard against a zero reading from coarse clocks on very short runs.
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        (
+            (tokens_per_iteration * iterations) as f64 / secs,
+            (text.len() * iterations) as f64 / secs,
+        )
+    };
+
+    ThroughputStats {
+        iterations,
+        tokens_per_iteration,
+        elapsed,
+        tokens_per_sec,
+        bytes_per_sec,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tantivy::tokenizer::{Language, SimpleTokenizer, StopWordFilter, TextAnalyzer};
+    use crate::filters::OuterPunctuationFilter;
+    use tantivy::tokenizer::{Language, LowerCaser, SimpleTokenizer, StopWordFilter, TextAnalyzer};
 
     #[test]
     fn test_count_tokens_basic() {
@@ -68,4 +1006,516 @@ mod tests {
         assert_eq!(count_tokens(&mut analyzer, "#hashtag @mention"), 2);
         assert_eq!(count_tokens(&mut analyzer, "...word..."), 1);
     }
+
+    #[test]
+    fn test_count_content_tokens() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer();
+        assert_eq!(count_tokens(&mut analyzer, "#### @@@ hello"), 3);
+        assert_eq!(
+            count_content_tokens(&mut analyzer, "#### @@@ hello", &['#', '@']),
+            1
+        );
+        assert_eq!(
+            count_content_tokens(&mut analyzer, "#hashtag @mention 🌳", &['#', '@']),
+            3
+        );
+
+        // Exception-only tokens are skipped even when they survive the analyzer
+        let mut analyzer = TextAnalyzer::from(WhitespaceTokenizer::default());
+        assert_eq!(
+            count_content_tokens(&mut analyzer, "### @@@ #@ hello", &['#', '@']),
+            1
+        );
+    }
+
+    #[test]
+    fn test_estimate_token_count() {
+        let inputs = [
+            "",
+            "   ",
+            "hello world",
+            "The cat sat on the mat.",
+            "John's #hashtag @mention -- !!! ...",
+            "tabs\tand\nnewlines\u{3000}ideographic space",
+        ];
+        let mut analyzer = crate::analyzers::kapiche_analyzer();
+        let mut analyzer_stop = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        for text in inputs {
+            let estimate = estimate_token_count(text);
+            assert!(estimate >= count_tokens(&mut analyzer, text), "{:?}", text);
+            assert!(
+                estimate >= count_tokens(&mut analyzer_stop, text),
+                "{:?}",
+                text
+            );
+        }
+        assert_eq!(estimate_token_count("The cat sat on the mat."), 6);
+        assert_eq!(estimate_token_count("  "), 0);
+    }
+
+    #[test]
+    fn test_preclean() {
+        assert_eq!(preclean("\u{FEFF}Hello world"), "Hello world");
+        assert_eq!(preclean("one\r\ntwo\rthree\n"), "one\ntwo\nthree\n");
+
+        // The BOM no longer ends up in the first token
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default()).build();
+        let text = "\u{FEFF}Hello\r\nworld\r\n";
+        assert_eq!(
+            leading_tokens(&mut analyzer, text, 2),
+            vec!["\u{FEFF}Hello", "world"]
+        );
+        assert_eq!(
+            leading_tokens(&mut analyzer, &preclean(text), 2),
+            vec!["Hello", "world"]
+        );
+    }
+
+    #[test]
+    fn test_preclean_clean_input_is_borrowed() {
+        assert!(matches!(preclean("already clean\ntext"), Cow::Borrowed(_)));
+        // Stripping a BOM alone doesn't need a copy either
+        assert!(matches!(preclean("\u{FEFF}text"), Cow::Borrowed("text")));
+        assert!(matches!(preclean("a\r\nb"), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_count_tokens_pred_stop() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let text = "The cat is on a big mat";
+
+        assert_eq!(
+            count_tokens_pred_stop(&mut analyzer, text, |t| t.chars().count() < 3),
+            4
+        );
+        assert_eq!(count_tokens_pred_stop(&mut analyzer, text, |_| false), 7);
+        assert_eq!(count_tokens_pred_stop(&mut analyzer, text, |_| true), 0);
+    }
+
+    #[test]
+    fn test_position_span() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let text = "The cat sat on the mat";
+
+        // "cat", "sat" and "mat" are kept at positions 1, 2 and 5
+        assert_eq!(count_tokens(&mut analyzer, text), 3);
+        assert_eq!(position_span(&mut analyzer, text), 5);
+        assert_eq!(position_span(&mut analyzer, "the of and"), 0);
+        assert_eq!(position_span(&mut analyzer, ""), 0);
+    }
+
+    #[test]
+    fn test_tokens_with_stopped() {
+        let mut analyzer = TextAnalyzer::builder(WhitespaceTokenizer::default())
+            .filter(LowerCaser)
+            .filter(OuterPunctuationFilter::new(vec!['#', '@']))
+            .filter(crate::filters::StopWordMarkFilter::new(
+                crate::filters::get_stopwords_filter_en(),
+            ))
+            .build();
+        let tokens = tokens_with_stopped(&mut analyzer, "The cat is on the mat.");
+
+        assert_eq!(
+            tokens,
+            vec![
+                ("the".to_string(), true),
+                ("cat".to_string(), false),
+                ("is".to_string(), true),
+                ("on".to_string(), true),
+                ("the".to_string(), true),
+                ("mat".to_string(), false),
+            ]
+        );
+        // Stopped tokens are still skipped by the counting utilities
+        assert_eq!(count_tokens(&mut analyzer, "The cat is on the mat."), 2);
+    }
+
+    #[test]
+    fn test_tokens_with_positions_repeated_tokens() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let tokens = tokens_with_positions(&mut analyzer, "The cat chased the cat, cat!");
+
+        assert_eq!(
+            tokens,
+            vec![
+                ("cat".to_string(), 1),
+                ("chased".to_string(), 2),
+                ("cat".to_string(), 4),
+                ("cat".to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unique_tokens() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let tokens = unique_tokens(&mut analyzer, "The cat, the CAT and the dog");
+
+        let expected: HashSet<String> = ["cat", "dog"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_unique_tokens_skip_punctuation_only() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let tokens = unique_tokens(&mut analyzer, "cat ... !!! cat");
+        let expected: HashSet<String> = ["cat"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(tokens, expected);
+        assert_eq!(sorted_unique_tokens(&mut analyzer, "-- cat ?"), vec!["cat"]);
+
+        let frequencies = document_frequencies(&mut analyzer, &["cat ...", "!!!"]);
+        assert_eq!(frequencies.get(""), None);
+        assert_eq!(frequencies.len(), 1);
+    }
+
+    #[test]
+    fn test_collectors_skip_punctuation_only() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let text = "cat ... dog";
+        assert_eq!(
+            tokens_with_positions(&mut analyzer, text),
+            vec![("cat".to_string(), 0), ("dog".to_string(), 2)]
+        );
+        assert_eq!(
+            tokens_with_stopped(&mut analyzer, text),
+            vec![("cat".to_string(), false), ("dog".to_string(), false)]
+        );
+        assert_eq!(
+            tokens_with_length_class(&mut analyzer, text),
+            vec![
+                ("cat".to_string(), LengthClass::Short),
+                ("dog".to_string(), LengthClass::Short)
+            ]
+        );
+        assert_eq!(
+            tokenize_tagged(&mut analyzer, &[("title", "!!!"), ("body", text)]),
+            vec![
+                ("body".to_string(), "cat".to_string()),
+                ("body".to_string(), "dog".to_string())
+            ]
+        );
+        // The emptied token still takes up a position
+        assert_eq!(
+            tokenize_multi_with_gap(&mut analyzer, &["cat ...", "dog"], 10),
+            vec![("cat".to_string(), 0), ("dog".to_string(), 12)]
+        );
+    }
+
+    #[test]
+    fn test_sorted_unique_tokens() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let tokens = sorted_unique_tokens(
+            &mut analyzer,
+            "zebra the apple Mango, apple ZEBRA banana mango",
+        );
+
+        assert_eq!(tokens, vec!["apple", "banana", "mango", "zebra"]);
+        assert!(sorted_unique_tokens(&mut analyzer, "the and of").is_empty());
+    }
+
+    #[test]
+    fn test_capacity_hint_does_not_change_output() {
+        let text = "The cat, the CAT and the dog chased John's ball";
+        let docs = [text, "A bird and the cat"];
+        let vocab: HashSet<String> = ["cat".to_string()].into_iter().collect();
+        let config = KapicheAnalyzerConfig::new()
+            .lowercase(true)
+            .stopwords(true)
+            .expected_tokens_per_doc(32);
+        // The hint is not part of the pipeline
+        let mut hinted = config.build();
+        let mut plain = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+
+        for capacity in [0, 1, config.tokens_per_doc_hint()] {
+            assert_eq!(
+                tokens_with_positions_with_capacity(&mut hinted, text, capacity),
+                tokens_with_positions(&mut plain, text)
+            );
+            assert_eq!(
+                unique_tokens_with_capacity(&mut hinted, text, capacity),
+                unique_tokens(&mut plain, text)
+            );
+            assert_eq!(
+                sorted_unique_tokens_with_capacity(&mut hinted, text, capacity),
+                sorted_unique_tokens(&mut plain, text)
+            );
+            assert_eq!(
+                tokens_with_stopped_with_capacity(&mut hinted, text, capacity),
+                tokens_with_stopped(&mut plain, text)
+            );
+            assert_eq!(
+                document_frequencies_with_capacity(&mut hinted, &docs, capacity),
+                document_frequencies(&mut plain, &docs)
+            );
+            assert_eq!(
+                oov_tokens_with_capacity(&mut hinted, text, &vocab, capacity),
+                oov_tokens(&mut plain, text, &vocab)
+            );
+            assert_eq!(
+                tokens_with_length_class_with_capacity(&mut hinted, text, capacity),
+                tokens_with_length_class(&mut plain, text)
+            );
+        }
+
+        // A large enough hint pre-sizes the outputs for the whole document
+        let hint = config.tokens_per_doc_hint();
+        assert!(tokens_with_positions_with_capacity(&mut hinted, text, hint).capacity() >= hint);
+        assert!(unique_tokens_with_capacity(&mut hinted, text, hint).capacity() >= hint);
+        assert!(tokens_with_stopped_with_capacity(&mut hinted, text, hint).capacity() >= hint);
+        assert!(oov_tokens_with_capacity(&mut hinted, text, &vocab, hint).capacity() >= hint);
+    }
+
+    #[test]
+    fn test_document_frequencies() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let docs = ["The cat sat", "The cat and the cat's dog", "A bird"];
+        let frequencies = document_frequencies(&mut analyzer, &docs);
+
+        // "cat" appears twice in the second document but is counted once
+        assert_eq!(frequencies.get("cat"), Some(&2));
+        assert_eq!(frequencies.get("sat"), Some(&1));
+        assert_eq!(frequencies.get("dog"), Some(&1));
+        assert_eq!(frequencies.get("bird"), Some(&1));
+        // Stopwords never appear
+        assert_eq!(frequencies.get("the"), None);
+        assert_eq!(frequencies.len(), 4);
+    }
+
+    #[test]
+    fn test_clean_text() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        assert_eq!(
+            clean_text(&mut analyzer, "The John's best!", " "),
+            "john best"
+        );
+        assert_eq!(clean_text(&mut analyzer, "cat ... dog", "|"), "cat|dog");
+        assert_eq!(clean_text(&mut analyzer, "", " "), "");
+    }
+
+    #[test]
+    fn test_leading_tokens() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let text = "The cat ... chased the dog";
+        assert_eq!(
+            leading_tokens(&mut analyzer, text, 2),
+            vec!["cat", "chased"]
+        );
+        // Asking for more tokens than available returns them all
+        assert_eq!(
+            leading_tokens(&mut analyzer, text, 10),
+            vec!["cat", "chased", "dog"]
+        );
+        assert!(leading_tokens(&mut analyzer, text, 0).is_empty());
+    }
+
+    #[test]
+    fn test_oov_tokens() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let vocab: HashSet<String> = ["cat", "sat", "mat"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let oov = oov_tokens(&mut analyzer, "The cat sat on the mta, the cta!", &vocab);
+        assert_eq!(oov, vec!["mta", "cta"]);
+
+        let oov = oov_tokens(&mut analyzer, "cta cat cta", &vocab);
+        assert_eq!(oov, vec!["cta", "cta"]);
+    }
+
+    #[test]
+    fn test_tokenize_multi_with_gap() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let tokens = tokenize_multi_with_gap(&mut analyzer, &["Hello world", "Foo bar"], 100);
+
+        assert_eq!(
+            tokens,
+            vec![
+                ("hello".to_string(), 0),
+                ("world".to_string(), 1),
+                ("foo".to_string(), 102),
+                ("bar".to_string(), 103),
+            ]
+        );
+        // The first token of field 2 is `position_gap` positions past the end of field 1
+        assert_eq!(tokens[2].1 - tokens[1].1, 100 + 1);
+    }
+
+    #[test]
+    fn test_tokenize_multi_with_gap_empty_field() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let tokens = tokenize_multi_with_gap(&mut analyzer, &["one", "", "two"], 10);
+
+        assert_eq!(
+            tokens,
+            vec![("one".to_string(), 0), ("two".to_string(), 21)]
+        );
+    }
+
+    #[test]
+    fn test_tokens_with_length_class() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let tokens = tokens_with_length_class(
+            &mut analyzer,
+            "cat café keyboard accessibility internationalization",
+        );
+
+        let classes: Vec<LengthClass> = tokens.iter().map(|(_, class)| *class).collect();
+        assert_eq!(
+            classes,
+            vec![
+                LengthClass::Short,
+                LengthClass::Medium,
+                LengthClass::Long,
+                LengthClass::VeryLong,
+                LengthClass::VeryLong,
+            ]
+        );
+        assert_eq!(tokens[1].0, "café");
+        assert_eq!(LengthClass::from_char_len(3), LengthClass::Short);
+        assert_eq!(LengthClass::from_char_len(4), LengthClass::Medium);
+        assert_eq!(LengthClass::from_char_len(12), LengthClass::Long);
+        assert_eq!(LengthClass::from_char_len(13), LengthClass::VeryLong);
+    }
+
+    #[test]
+    fn test_tokenize_tagged() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let tokens = tokenize_tagged(
+            &mut analyzer,
+            &[("title", "Great Product"), ("body", "The product broke")],
+        );
+
+        let expected: Vec<(String, String)> = [
+            ("title", "great"),
+            ("title", "product"),
+            ("body", "product"),
+            ("body", "broke"),
+        ]
+        .iter()
+        .map(|(field, text)| (field.to_string(), text.to_string()))
+        .collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_dual_tokenize() {
+        let text = "The John's dog and it's ball, I'm sure!";
+        let (all, content) = dual_tokenize(text);
+
+        let mut with_stopwords = crate::analyzers::kapiche_analyzer_lower();
+        let mut without_stopwords = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let texts = |analyzer: &mut TextAnalyzer| -> Vec<String> {
+            tokens_with_positions(analyzer, text)
+                .into_iter()
+                .map(|(text, _)| text)
+                .collect()
+        };
+        assert_eq!(all, texts(&mut with_stopwords));
+        assert_eq!(content, texts(&mut without_stopwords));
+
+        // The outputs differ exactly by the stopwords
+        let removed: Vec<&String> = all.iter().filter(|t| !content.contains(t)).collect();
+        assert_eq!(removed, vec!["the", "and", "it", "i'm"]);
+
+        // A custom configuration gives the same split for its own pipeline
+        let config = KapicheAnalyzerConfig::new()
+            .lowercase(true)
+            .leading_allow(vec![])
+            .stopwords(true);
+        let (all, content) =
+            dual_tokenize_with(&mut config.clone().mark_stopwords(true).build(), text);
+        assert_eq!(all, texts(&mut config.clone().stopwords(false).build()));
+        assert_eq!(content, texts(&mut config.build()));
+    }
+
+    #[test]
+    fn test_diff_analyzers_stopwords() {
+        let mut current = crate::analyzers::kapiche_analyzer_lower();
+        let mut proposed = crate::analyzers::kapiche_analyzer_lower_with_stopwords();
+        let diff = diff_analyzers(&mut current, &mut proposed, "The cat and the dog");
+
+        assert_eq!(diff.only_in_a, vec!["the", "and", "the"]);
+        assert!(diff.only_in_b.is_empty());
+        // Removing stopwords keeps the positions of the other tokens
+        assert!(diff.moved.is_empty());
+
+        let mut same = current.clone();
+        let diff = diff_analyzers(&mut current, &mut same, "The cat and the dog");
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_analyzers_moved_tokens() {
+        let mut a = crate::analyzers::kapiche_analyzer_lower();
+        let mut b = TextAnalyzer::from(SimpleTokenizer::default());
+        let diff = diff_analyzers(&mut a, &mut b, "well-known cat");
+
+        assert_eq!(diff.only_in_a, vec!["well-known"]);
+        assert_eq!(diff.only_in_b, vec!["well", "known"]);
+        assert_eq!(diff.moved, vec![("cat".to_string(), 1, 2)]);
+    }
+
+    #[test]
+    fn test_corpus_stats() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let stats = corpus_stats(&mut analyzer, &["The kitten ran", "A dog, the kitten! ..."]);
+
+        assert_eq!(stats.documents, 2);
+        // the, kitten, ran, a, dog, the, kitten ("..." is emptied and not counted)
+        assert_eq!(stats.total_tokens, 7);
+        assert_eq!(stats.unique_tokens, 5);
+        assert!((stats.mean_token_length - 25.0 / 7.0).abs() < 1e-9);
+        assert!((stats.stopword_ratio - 3.0 / 7.0).abs() < 1e-9);
+
+        assert_eq!(corpus_stats(&mut analyzer, &[]), CorpusStats::default());
+    }
+
+    #[test]
+    fn test_filter_removal_stats() {
+        let config = KapicheAnalyzerConfig::new().lowercase(true).stopwords(true);
+        let docs = ["The cat sat!", "... a dog's ???"];
+        let stats = filter_removal_stats(&config, &docs);
+
+        assert_eq!(
+            stats,
+            FilterStats {
+                documents: 2,
+                input_tokens: 7,
+                // "..." and "???"
+                punctuation_emptied: 2,
+                // "the" and "a"
+                stopwords_removed: 2,
+                // "cat", "sat", "dog"
+                output_tokens: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_removal_stats_without_stopwords() {
+        let config = KapicheAnalyzerConfig::new();
+        let stats = filter_removal_stats(&config, &["The cat sat!"]);
+        assert_eq!(stats.stopwords_removed, 0);
+        assert_eq!(stats.output_tokens, 3);
+    }
+
+    #[test]
+    fn test_measure_throughput() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let stats = measure_throughput(&mut analyzer, "John's #hashtag @mention", 10);
+
+        assert_eq!(stats.iterations, 10);
+        assert_eq!(stats.tokens_per_iteration, 3);
+        assert!(stats.tokens_per_sec > 0.0);
+        assert!(stats.bytes_per_sec > 0.0);
+    }
+
+    #[test]
+    fn test_measure_throughput_no_iterations() {
+        let mut analyzer = crate::analyzers::kapiche_analyzer_lower();
+        let stats = measure_throughput(&mut analyzer, "hello world", 0);
+
+        assert_eq!(stats.tokens_per_sec, 0.0);
+        assert_eq!(stats.bytes_per_sec, 0.0);
+    }
 }